@@ -0,0 +1,134 @@
+use std::{
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use iced::futures::channel::oneshot;
+use turing_machine::{Breakpoints, Machine, Table, Tape, TestCase, TestResult};
+
+/// Run of the machine off the UI thread until it halts,
+/// reaches a breakpoint, uses up its step budget or is cancelled
+pub struct BackgroundRun {
+    /// Number of steps executed so far, updated by the running thread
+    executed_steps: Arc<AtomicUsize>,
+    is_cancelled: Arc<AtomicBool>,
+    step_budget: usize,
+
+    /// Identifies the run, so the result of an earlier, cancelled run can be told apart
+    generation: usize,
+}
+
+impl BackgroundRun {
+    /// Runs the machine on its own thread. Returns the run and the future which
+    /// resolves to the machine after its last step, or None if the thread panicked
+    pub fn start(
+        mut machine: Machine,
+        table: Table,
+        breakpoints: Breakpoints,
+        step_budget: usize,
+        generation: usize,
+    ) -> (Self, impl Future<Output = Option<Machine>>) {
+        let run = Self {
+            executed_steps: Arc::new(AtomicUsize::new(0)),
+            is_cancelled: Arc::new(AtomicBool::new(false)),
+            step_budget,
+            generation,
+        };
+
+        let executed_steps = run.executed_steps.clone();
+        let is_cancelled = run.is_cancelled.clone();
+        let (sender, receiver) = oneshot::channel();
+
+        thread::spawn(move || {
+            for step in 1..=step_budget {
+                machine.next_step(&table);
+                executed_steps.store(step, Ordering::Relaxed);
+
+                if machine.is_halted()
+                    || machine.is_at_breakpoint(&breakpoints)
+                    || is_cancelled.load(Ordering::Relaxed)
+                {
+                    break;
+                }
+            }
+
+            // The receiver is gone if the application was closed
+            let _ = sender.send(machine);
+        });
+
+        (run, async move { receiver.await.ok() })
+    }
+
+    pub fn get_executed_steps(&self) -> usize {
+        self.executed_steps.load(Ordering::Relaxed)
+    }
+
+    pub fn get_step_budget(&self) -> usize {
+        self.step_budget
+    }
+
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Stops the run after the current step. The machine is still returned by its future
+    pub fn cancel(&self) {
+        self.is_cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Run of test cases off the UI thread, which can be cancelled between the tests
+pub struct BackgroundTests {
+    is_cancelled: Arc<AtomicBool>,
+
+    /// Identifies the run, so the results of an earlier, replaced run can be told apart
+    generation: usize,
+}
+
+impl BackgroundTests {
+    /// Runs the tests on their own thread. Returns the run and the future which resolves
+    /// to the results paired with the indices of their tests,
+    /// or None if the run was cancelled or the thread panicked
+    pub fn start(
+        tests: Vec<(usize, TestCase)>,
+        table: Table,
+        tape: Tape,
+        generation: usize,
+    ) -> (Self, impl Future<Output = Option<Vec<(usize, TestResult)>>>) {
+        let run = Self {
+            is_cancelled: Arc::new(AtomicBool::new(false)),
+            generation,
+        };
+
+        let is_cancelled = run.is_cancelled.clone();
+        let (sender, receiver) = oneshot::channel();
+
+        thread::spawn(move || {
+            let mut results = Vec::with_capacity(tests.len());
+            for (index, test) in tests {
+                if is_cancelled.load(Ordering::Relaxed) {
+                    return;
+                }
+                results.push((index, test.run(&table, &tape)));
+            }
+
+            // The receiver is gone if the application was closed
+            let _ = sender.send(results);
+        });
+
+        (run, async move { receiver.await.ok() })
+    }
+
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Stops the run before the next test. Its future resolves to None then
+    pub fn cancel(&self) {
+        self.is_cancelled.store(true, Ordering::Relaxed);
+    }
+}
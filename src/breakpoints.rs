@@ -0,0 +1,48 @@
+use std::collections::HashSet;
+
+/// Cells and whole states of a table
+/// before which a running machine should pause
+#[derive(Debug, Clone, Default)]
+pub struct Breakpoints {
+    /// Pairs of state and character identifying single cells
+    cells: HashSet<(usize, char)>,
+
+    /// States whose all cells are breakpoints
+    states: HashSet<usize>,
+}
+
+impl Breakpoints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.states.clear();
+    }
+
+    pub fn is_cell_breakpoint(&self, state: usize, character: char) -> bool {
+        self.cells.contains(&(state, character))
+    }
+
+    pub fn is_state_breakpoint(&self, state: usize) -> bool {
+        self.states.contains(&state)
+    }
+
+    /// Returns true if the cell is a breakpoint itself or belongs to a breakpoint state
+    pub fn is_hit(&self, state: usize, character: char) -> bool {
+        self.is_state_breakpoint(state) || self.is_cell_breakpoint(state, character)
+    }
+
+    pub fn toggle_cell(&mut self, state: usize, character: char) {
+        if !self.cells.remove(&(state, character)) {
+            self.cells.insert((state, character));
+        }
+    }
+
+    pub fn toggle_state(&mut self, state: usize) {
+        if !self.states.remove(&state) {
+            self.states.insert(state);
+        }
+    }
+}
@@ -0,0 +1,508 @@
+use std::{
+    fs::File,
+    io::{stdout, BufWriter, Write},
+    path::PathBuf,
+};
+
+use image::ImageFormat;
+use turing_machine::{
+    constants::{EMPTY_CHAR, JSON_FILE_EXTENSION},
+    lint, Gradebook, HaltReason, Machine, Project, TapeMode, TestSuite,
+};
+
+use crate::{
+    config::load_config,
+    language::{english::ENGLISH_LANGUAGE, Language},
+    space_time_diagram::{SpaceTimeDiagram, DEFAULT_CELL_SIZE, DEFAULT_SPACE_TIME_STEPS},
+    tape_animation::{TapeAnimation, DEFAULT_ANIMATION_STEPS, DEFAULT_FRAME_DELAY},
+};
+
+pub const RUN_COMMAND: &str = "run";
+pub const CONVERT_COMMAND: &str = "convert";
+pub const LINT_COMMAND: &str = "lint";
+pub const TEST_COMMAND: &str = "test";
+pub const GRADE_COMMAND: &str = "grade";
+const DEFAULT_MAX_STEPS: usize = 1_000_000;
+const CONVERT_USAGE: &str = "Usage: turing-machine convert <input file> <output file>";
+const LINT_USAGE: &str = "Usage: turing-machine lint <file>";
+const TEST_USAGE: &str = "Usage: turing-machine test <file> [--suite <tests.json>]";
+const GRADE_USAGE: &str = "Usage: turing-machine grade <directory> <tests.json> [--output <gradebook.csv|gradebook.json>] [--max-steps <steps>]";
+const RUN_USAGE: &str = "Usage: turing-machine run <file.mt|file.mtp|file.json> [--tape <chars>] [--cursor <position>] [--tape-length <length>] [--bounded|--unbounded] [--max-steps <steps>] [--detect-loops] [--space-time <image.png>] [--space-time-steps <steps>] [--cell-size <pixels>] [--gif <animation.gif>] [--gif-steps <steps>] [--frame-delay <milliseconds>] [--language <English|Polski>]";
+
+/// Connects stdout and stderr to the console from which the program was started.
+/// The program uses the GUI subsystem on Windows, so it doesn't get a console of its own
+#[cfg(windows)]
+pub fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // Without a parent console the output is discarded, as in the GUI
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn attach_console() {}
+
+/// Options of the `run` command.
+/// Tape settings which are None are taken from the opened file
+struct RunOptions {
+    file_path: PathBuf,
+    tape_chars: Option<String>,
+    cursor_position: Option<isize>,
+    tape_length: Option<usize>,
+    tape_mode: Option<TapeMode>,
+    max_steps: usize,
+
+    /// Halt the machine when it repeats a configuration
+    detect_loops: bool,
+
+    /// Path of the space-time diagram of the first `space_time_steps` steps,
+    /// None if it isn't saved
+    space_time_path: Option<PathBuf>,
+    space_time_steps: usize,
+    cell_size: u32,
+
+    /// Path of the animation of the first `animation_steps` steps, None if it isn't saved
+    animation_path: Option<PathBuf>,
+    animation_steps: usize,
+    frame_delay: u32,
+
+    /// Language of the labels of the animation, the language of the GUI by default
+    language: &'static Language,
+}
+
+/// Runs the `run` command with arguments following it and returns the process exit code,
+/// which is 1 if the machine reaches the step limit, runs out of the tape or loops forever
+pub fn run(args: &[String]) -> i32 {
+    let options = match parse_run_options(args) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{}\n{}", e, RUN_USAGE);
+            return 2;
+        }
+    };
+
+    let Project {
+        table, mut tape, ..
+    } = match Project::new_from_file(&options.file_path) {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("Failed to open {}: {}", options.file_path.display(), e);
+            return 1;
+        }
+    };
+
+    if let Some(mode) = options.tape_mode {
+        tape.set_mode(mode);
+    }
+    if let Some(length) = options.tape_length {
+        tape.set_length(length);
+    }
+    if let Some(chars) = options.tape_chars {
+        let chars_number = chars.chars().filter(|c| !c.is_whitespace()).count();
+        tape.set_chars(chars);
+        if tape.get_chars_without_margin().len() < chars_number {
+            eprintln!(
+                "The tape has {} characters, but the bounded tape has only {} cells\n{}",
+                chars_number,
+                tape.get_length(),
+                RUN_USAGE
+            );
+            return 2;
+        }
+    }
+    if let Some(position) = options.cursor_position {
+        tape.set_cursor_position(position);
+    }
+
+    let mut machine = Machine::without_history();
+    machine.set_cycle_detection(options.detect_loops);
+    machine.reset(tape);
+
+    // Rows are recorded only if the diagram is saved
+    let mut space_time_diagram = options.space_time_path.as_ref().map(|_| {
+        let mut diagram = SpaceTimeDiagram::new();
+        diagram.push(machine.get_tape());
+        diagram
+    });
+    let mut animation = options.animation_path.as_ref().map(|_| {
+        let mut animation = TapeAnimation::new();
+        animation.push(&machine);
+        animation
+    });
+
+    while !machine.is_halted() && machine.get_step() < options.max_steps {
+        machine.next_step(&table);
+        if let Some(diagram) = &mut space_time_diagram {
+            if machine.get_step() <= options.space_time_steps {
+                diagram.push(machine.get_tape());
+            }
+        }
+        if let Some(animation) = &mut animation {
+            if machine.get_step() <= options.animation_steps {
+                animation.push(&machine);
+            }
+        }
+    }
+
+    if let (Some(diagram), Some(path)) = (space_time_diagram, &options.space_time_path) {
+        let image = diagram.render(table.get_characters(), options.cell_size);
+        if let Err(e) = image.save_with_format(path, ImageFormat::Png) {
+            eprintln!("Failed to save {}: {}", path.display(), e);
+            return 1;
+        }
+    }
+
+    if let (Some(animation), Some(path)) = (animation, &options.animation_path) {
+        let texts = &options.language.simulation_scene_texts;
+        let res = animation.save(
+            path,
+            &table,
+            texts.state_text,
+            texts.step_text,
+            options.frame_delay,
+        );
+        if let Err(e) = res {
+            eprintln!("Failed to save {}: {}", path.display(), e);
+            return 1;
+        }
+    }
+
+    let halt_reason = match machine.get_halt_reason() {
+        Some(reason) => reason.to_string(),
+        None => "step limit reached".to_string(),
+    };
+
+    let tape = machine.get_tape();
+    let tape_chars: String = tape.get_chars_with_margin().iter().collect();
+    println!("Tape: {}", tape_chars.trim_matches(EMPTY_CHAR));
+    println!("Cursor position: {}", tape.get_cursor_position());
+    println!("State: {}", machine.get_state());
+    println!("Steps: {}", machine.get_step());
+    println!("Halt reason: {}", halt_reason);
+
+    run_exit_code(machine.get_halt_reason())
+}
+
+/// A machine which doesn't halt by itself or runs out of the tape fails the `run` command
+fn run_exit_code(halt_reason: Option<HaltReason>) -> i32 {
+    match halt_reason {
+        None | Some(HaltReason::OutOfTape | HaltReason::LoopsForever) => 1,
+        Some(_) => 0,
+    }
+}
+
+/// Runs the `convert` command, which saves a file in the format matching
+/// the extension of the output file, and returns the process exit code
+pub fn convert(args: &[String]) -> i32 {
+    let (input_path, output_path) = match args {
+        [input, output] => (PathBuf::from(input), PathBuf::from(output)),
+        _ => {
+            eprintln!("{}", CONVERT_USAGE);
+            return 2;
+        }
+    };
+
+    let project = match Project::new_from_file(&input_path) {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("Failed to open {}: {}", input_path.display(), e);
+            return 1;
+        }
+    };
+
+    if let Err(e) = project.write_to_file(&output_path) {
+        eprintln!("Failed to save {}: {}", output_path.display(), e);
+        return 1;
+    }
+
+    0
+}
+
+/// Runs the `lint` command, which prints warnings about the table of the file,
+/// and returns the process exit code, which is 1 if there are any warnings
+pub fn lint_file(args: &[String]) -> i32 {
+    let path = match args {
+        [path] => PathBuf::from(path),
+        _ => {
+            eprintln!("{}", LINT_USAGE);
+            return 2;
+        }
+    };
+
+    let project = match Project::new_from_file(&path) {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("Failed to open {}: {}", path.display(), e);
+            return 1;
+        }
+    };
+
+    let warnings = lint(&project.table);
+    for warning in &warnings {
+        println!("{}", warning);
+    }
+
+    match warnings.is_empty() {
+        true => 0,
+        false => 1,
+    }
+}
+
+/// Runs the `test` command, which runs the test cases saved in the project
+/// or in a separate suite file, and returns the process exit code
+pub fn test(args: &[String]) -> i32 {
+    let (file_path, suite_path) = match args {
+        [file] => (PathBuf::from(file), None),
+        [file, option, suite] if option == "--suite" => {
+            (PathBuf::from(file), Some(PathBuf::from(suite)))
+        }
+        _ => {
+            eprintln!("{}", TEST_USAGE);
+            return 2;
+        }
+    };
+
+    let project = match Project::new_from_file(&file_path) {
+        Ok(project) => project,
+        Err(e) => {
+            eprintln!("Failed to open {}: {}", file_path.display(), e);
+            return 1;
+        }
+    };
+
+    let tests = match suite_path {
+        Some(path) => match TestSuite::new_from_file(&path) {
+            Ok(suite) => suite.tests,
+            Err(e) => {
+                eprintln!("Failed to open {}: {}", path.display(), e);
+                return 1;
+            }
+        },
+        None => project.tests,
+    };
+
+    if tests.is_empty() {
+        eprintln!("No test cases found");
+        return 1;
+    }
+
+    let mut failed_tests = 0;
+    for test in &tests {
+        let result = test.run(&project.table, &project.tape);
+        if result.is_passed() {
+            println!("PASS {} ({} steps)", test.name, result.steps);
+            continue;
+        }
+
+        failed_tests += 1;
+        println!("FAIL {} ({} steps)", test.name, result.steps);
+        for failure in result.failures {
+            println!("  {}", failure);
+        }
+    }
+
+    println!(
+        "{} passed, {} failed",
+        tests.len() - failed_tests,
+        failed_tests
+    );
+
+    match failed_tests {
+        0 => 0,
+        _ => 1,
+    }
+}
+
+/// Runs the `grade` command, which runs a test suite on every .mt file in a directory
+/// and writes the results as CSV, or JSON if the output file has the .json extension.
+/// Returns the process exit code, which doesn't depend on the results
+pub fn grade(args: &[String]) -> i32 {
+    let mut positional_args = Vec::new();
+    let mut output_path = None;
+    let mut max_steps = None;
+
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        let mut value = || args_iter.next().ok_or(format!("Missing value for {}", arg));
+        let res = match arg.as_str() {
+            "--output" => value().map(|v| output_path = Some(PathBuf::from(v))),
+            "--max-steps" => value()
+                .and_then(|v| parse_number(arg, v))
+                .map(|steps| max_steps = Some(steps)),
+            a if a.starts_with("--") => Err(format!("Unknown option {}", a)),
+            a => {
+                positional_args.push(PathBuf::from(a));
+                Ok(())
+            }
+        };
+
+        if let Err(e) = res {
+            eprintln!("{}\n{}", e, GRADE_USAGE);
+            return 2;
+        }
+    }
+
+    let (directory, suite_path) = match positional_args.as_slice() {
+        [directory, suite_path] => (directory, suite_path),
+        _ => {
+            eprintln!("{}", GRADE_USAGE);
+            return 2;
+        }
+    };
+
+    let suite = match TestSuite::new_from_file(suite_path) {
+        Ok(suite) => suite,
+        Err(e) => {
+            eprintln!("Failed to open {}: {}", suite_path.display(), e);
+            return 1;
+        }
+    };
+
+    let gradebook = match Gradebook::new_from_directory(directory, &suite.tests, max_steps) {
+        Ok(gradebook) => gradebook,
+        Err(e) => {
+            eprintln!("Failed to read {}: {}", directory.display(), e);
+            return 1;
+        }
+    };
+
+    let res = match &output_path {
+        Some(path) => File::create(path).and_then(|file| {
+            let mut buffer = BufWriter::new(file);
+            let is_json =
+                path.extension().and_then(|ext| ext.to_str()) == Some(JSON_FILE_EXTENSION);
+            match is_json {
+                true => gradebook.write_json(&mut buffer),
+                false => gradebook.write_csv(&mut buffer),
+            }?;
+            buffer.flush()
+        }),
+        None => gradebook.write_csv(&mut stdout()),
+    };
+
+    if let Err(e) = res {
+        let output = match &output_path {
+            Some(path) => path.display().to_string(),
+            None => "the gradebook".to_string(),
+        };
+        eprintln!("Failed to save {}: {}", output, e);
+        return 1;
+    }
+
+    // The summary doesn't mix with the CSV printed to the standard output
+    if output_path.is_some() {
+        for submission in &gradebook.submissions {
+            match &submission.error {
+                Some(error) => println!("{}: {}", submission.file, error),
+                None => println!(
+                    "{}: {}/{}",
+                    submission.file, submission.passed_tests, gradebook.tests_number
+                ),
+            }
+        }
+    }
+
+    0
+}
+
+fn parse_run_options(args: &[String]) -> Result<RunOptions, String> {
+    let mut file_path = None;
+    let mut tape_chars = None;
+    let mut cursor_position = None;
+    let mut tape_length = None;
+    let mut tape_mode = None;
+    let mut max_steps = DEFAULT_MAX_STEPS;
+    let mut detect_loops = false;
+    let mut space_time_path = None;
+    let mut space_time_steps = DEFAULT_SPACE_TIME_STEPS;
+    let mut cell_size = DEFAULT_CELL_SIZE;
+    let mut animation_path = None;
+    let mut animation_steps = DEFAULT_ANIMATION_STEPS;
+    let mut frame_delay = DEFAULT_FRAME_DELAY;
+    let mut language = load_config().map_or(ENGLISH_LANGUAGE, |(language, _)| language);
+
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        let mut value = || args_iter.next().ok_or(format!("Missing value for {}", arg));
+
+        match arg.as_str() {
+            "--tape" => tape_chars = Some(value()?.clone()),
+            "--cursor" => cursor_position = Some(parse_number(arg, value()?)?),
+            "--tape-length" => tape_length = Some(parse_number(arg, value()?)?),
+            "--bounded" => tape_mode = Some(TapeMode::Bounded),
+            "--unbounded" => tape_mode = Some(TapeMode::Unbounded),
+            "--max-steps" => max_steps = parse_number(arg, value()?)?,
+            "--detect-loops" => detect_loops = true,
+            "--space-time" => space_time_path = Some(PathBuf::from(value()?)),
+            "--space-time-steps" => space_time_steps = parse_number(arg, value()?)?,
+            "--cell-size" => match parse_number(arg, value()?)? {
+                0 => return Err("Cell size has to be positive".to_string()),
+                size => cell_size = size,
+            },
+            "--gif" => animation_path = Some(PathBuf::from(value()?)),
+            "--gif-steps" => animation_steps = parse_number(arg, value()?)?,
+            "--frame-delay" => frame_delay = parse_number(arg, value()?)?,
+            "--language" => {
+                let name = value()?;
+                language = name.parse().or(Err(format!("Unknown language {}", name)))?;
+            }
+            a if a.starts_with("--") => return Err(format!("Unknown option {}", a)),
+            a if file_path.is_none() => file_path = Some(PathBuf::from(a)),
+            a => return Err(format!("Unexpected argument {}", a)),
+        }
+    }
+
+    Ok(RunOptions {
+        file_path: file_path.ok_or("Missing file path".to_string())?,
+        tape_chars,
+        cursor_position,
+        tape_length,
+        tape_mode,
+        max_steps,
+        detect_loops,
+        space_time_path,
+        space_time_steps,
+        cell_size,
+        animation_path,
+        animation_steps,
+        frame_delay,
+        language,
+    })
+}
+
+fn parse_number<N: std::str::FromStr>(option: &str, value: &str) -> Result<N, String> {
+    value
+        .parse()
+        .or(Err(format!("Invalid value {} for {}", value, option)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn machines_which_dont_halt_by_themselves_fail() {
+        assert_eq!(run_exit_code(None), 1);
+        assert_eq!(run_exit_code(Some(HaltReason::OutOfTape)), 1);
+        assert_eq!(run_exit_code(Some(HaltReason::LoopsForever)), 1);
+    }
+
+    #[test]
+    fn halted_machines_succeed() {
+        for reason in [
+            HaltReason::NoRule,
+            HaltReason::Stop,
+            HaltReason::Accepted,
+            HaltReason::Rejected,
+        ] {
+            assert_eq!(run_exit_code(Some(reason)), 0, "{}", reason);
+        }
+    }
+}
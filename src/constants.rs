@@ -1,8 +1,14 @@
 use crate::task::Direction;
-use iced::Font;
-use image::ImageFormat;
 
 pub const FILE_EXTENSION: &str = "mt";
+pub const PROJECT_FILE_EXTENSION: &str = "mtp";
+pub const JSON_FILE_EXTENSION: &str = "json";
+pub const JFLAP_FILE_EXTENSION: &str = "jff";
+pub const YAML_FILE_EXTENSION: &str = "yaml";
+pub const YML_FILE_EXTENSION: &str = "yml";
+pub const BBCHALLENGE_FILE_EXTENSION: &str = "bb";
+pub const DOT_FILE_EXTENSION: &str = "dot";
+pub const GV_FILE_EXTENSION: &str = "gv";
 pub const EMPTY_CHAR: char = '#';
 pub const DEFAULT_TABLE_CHARS: &str = const_str::concat!(EMPTY_CHAR, "abc");
 pub const DEFAULT_STATE: usize = 0;
@@ -11,19 +17,18 @@ pub const DEFAULT_TASK_DIRECTION: Direction = Direction::Stop;
 pub const DEFAULT_TAPE_CHARS_NUMBER: usize = 101;
 pub const MAX_TAPE_LENGTH: usize = 250;
 pub const MIN_TAPE_LENGTH: usize = 1;
-pub const MAX_STATES_NUMBER: usize = 100;
+pub const MAX_STATES_NUMBER: usize = 1000;
+pub const MAX_STATE_DIGITS: usize = 3;
 pub const MIN_STATES_NUMBER: usize = 1;
-pub const MAX_TAPE_FONT_SIZE: u16 = 30;
-pub const MIN_TAPE_FONT_SIZE: u16 = 14;
-pub const SCALE_FACTOR_STEP: f64 = 0.1;
-pub const MACHINE_SELF_TIMER_INTERVAL_STEP: u32 = 100;
-pub const MIN_MACHINE_SELF_TIMER_INTERVAL: u32 = 1;
-pub const MAX_MACHINE_SELF_TIMER_INTERVAL: u32 = 1000;
-pub const STOP_MACHINE_SELF_TIMER_VALUE: u32 =
-    MAX_MACHINE_SELF_TIMER_INTERVAL + MACHINE_SELF_TIMER_INTERVAL_STEP;
-pub const TAPE_FONT: Font = Font::External {
-    name: "Roboto Mono",
-    bytes: include_bytes!("../RobotoMono/fonts/ttf/RobotoMono-Medium.ttf"),
-};
-pub const ICON_BYTES: &[u8] = include_bytes!("../icon.ico");
-pub const ICON_FORMAT: ImageFormat = ImageFormat::Ico;
+pub const MAX_HISTORY_LENGTH: usize = 100;
+pub const DEFAULT_TEST_MAX_STEPS: usize = 10_000;
+
+/// Number of the last steps of the simulation which can be undone
+pub const MAX_UNDO_STEPS: usize = 100_000;
+
+/// Number of configurations remembered by the cycle detector before it forgets them
+/// and starts again from the current one, so only cycles shorter than it are found
+pub const MAX_CYCLE_DETECTOR_VISITS: usize = 1_000_000;
+
+/// Number of configurations at each edge of the tape compared when looking for translated cycles
+pub const MAX_EDGE_RECORDS: usize = 100;
@@ -0,0 +1,429 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+};
+
+use crate::{
+    constants::{EMPTY_CHAR, MAX_CYCLE_DETECTOR_VISITS, MAX_EDGE_RECORDS},
+    machine::StepDelta,
+    tape::{Tape, TapeMode},
+};
+
+/// Configuration recorded after a step, needed to undo that step
+#[derive(Debug, Clone, Copy)]
+struct Visit {
+    step: usize,
+    state: usize,
+
+    /// Hash of the state, the cursor position and the tape
+    hash: u64,
+
+    /// Position of the cell written in the step and its characters before and after it,
+    /// None for the first recorded configuration
+    write: Option<(isize, char, char)>,
+
+    /// Hash of the tape only, updated with every written character
+    tape_hash: u64,
+
+    cursor_position: isize,
+
+    /// Range of positions outside of which all cells are blank
+    min_position: isize,
+    max_position: isize,
+}
+
+/// Configuration in which the cursor is at the edge of the used part of the tape,
+/// farther than in all previous records
+#[derive(Debug, Clone)]
+struct EdgeRecord {
+    step: usize,
+    state: usize,
+
+    /// Position of the cursor measured towards the edge
+    position: isize,
+
+    /// Characters from the cursor away from the edge up to the other end of the used part
+    chars: Vec<char>,
+}
+
+/// Finds configurations of a machine which will never halt.
+///
+/// A machine loops forever if it repeats a configuration of its state,
+/// cursor position and tape, or if it repeats the part of the tape which it reads
+/// at the edge of the tape, only moved by some cells towards the blank cells
+#[derive(Debug, Clone, Default)]
+pub struct CycleDetector {
+    /// Configurations in the order of steps
+    visits: Vec<Visit>,
+
+    /// Step of the last configuration with the given hash
+    hashes: HashMap<u64, usize>,
+
+    /// Records on the left and the right edge of the tape
+    left_records: Vec<EdgeRecord>,
+    right_records: Vec<EdgeRecord>,
+}
+
+impl CycleDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forgets all configurations and starts looking for cycles from the current one
+    pub fn reset(&mut self, step: usize, state: usize, tape: &Tape) {
+        self.visits.clear();
+        self.hashes.clear();
+        self.left_records.clear();
+        self.right_records.clear();
+
+        let chars = tape.get_chars_with_margin();
+        let first_char_position = tape.get_first_char_position();
+        let cursor_position = tape.get_cursor_position();
+        let (mut min_position, mut max_position) = (cursor_position, cursor_position);
+        let mut tape_hash = 0;
+
+        for (i, c) in chars.iter().enumerate() {
+            if *c != EMPTY_CHAR {
+                let position = i as isize - first_char_position;
+                min_position = min_position.min(position);
+                max_position = max_position.max(position);
+                tape_hash ^= hash_cell(position, *c);
+            }
+        }
+
+        self.visit(
+            Visit {
+                step,
+                state,
+                hash: 0,
+                write: None,
+                tape_hash,
+                cursor_position,
+                min_position,
+                max_position,
+            },
+            state,
+            tape,
+        );
+    }
+
+    /// Records the configuration after the step described by `delta`
+    /// and returns true if the machine loops forever
+    pub fn is_looping(&mut self, state: usize, tape: &Tape, delta: &StepDelta) -> bool {
+        let previous = match self.visits.last() {
+            Some(previous) => *previous,
+            None => return false,
+        };
+
+        let new_char = tape.get_chars_with_margin()
+            [(tape.get_first_char_position() + delta.cursor_position) as usize];
+        let mut tape_hash = previous.tape_hash;
+        for c in [delta.character, new_char] {
+            if c != EMPTY_CHAR {
+                tape_hash ^= hash_cell(delta.cursor_position, c);
+            }
+        }
+
+        let cursor_position = tape.get_cursor_position();
+        let visit = Visit {
+            step: previous.step + 1,
+            state,
+            hash: 0,
+            write: Some((delta.cursor_position, delta.character, new_char)),
+            tape_hash,
+            cursor_position,
+            min_position: previous.min_position.min(cursor_position),
+            max_position: previous.max_position.max(cursor_position),
+        };
+
+        self.visit(visit, state, tape)
+    }
+
+    /// Forgets configurations recorded after `step`, e.g. when a step is undone.
+    /// Starts again from the current configuration if it was recorded later
+    pub fn rewind(&mut self, step: usize, state: usize, tape: &Tape) {
+        while self.visits.last().is_some_and(|visit| visit.step > step) {
+            self.visits.pop();
+        }
+        self.hashes.retain(|_, visit_step| *visit_step <= step);
+
+        if self.visits.is_empty() {
+            self.reset(step, state, tape);
+            return;
+        }
+        self.left_records.retain(|record| record.step <= step);
+        self.right_records.retain(|record| record.step <= step);
+    }
+
+    /// Saves `visit` and returns true if it repeats an earlier configuration
+    /// or completes a translated cycle
+    fn visit(&mut self, mut visit: Visit, state: usize, tape: &Tape) -> bool {
+        let cursor_position = tape.get_cursor_position();
+
+        let mut hasher = DefaultHasher::new();
+        (state, cursor_position, visit.tape_hash).hash(&mut hasher);
+        visit.hash = hasher.finish();
+
+        // The configurations are forgotten at once to bound the memory,
+        // the following visits still carry the range of the used part of the tape
+        if self.visits.len() == MAX_CYCLE_DETECTOR_VISITS {
+            self.visits.clear();
+            self.hashes.clear();
+            self.left_records.clear();
+            self.right_records.clear();
+        }
+
+        // Equal hashes are only a hint, the configurations are compared before halting.
+        // After a collision only the later configuration is remembered,
+        // so a real cycle is then found one period later
+        if let Some(&earlier_step) = self.hashes.get(&visit.hash) {
+            if self.is_same_configuration(earlier_step, &visit) {
+                return true;
+            }
+        }
+        self.visits.push(visit);
+        self.hashes.insert(visit.hash, visit.step);
+
+        // A bounded tape ends before a translated cycle could repeat forever
+        if tape.get_mode() == TapeMode::Bounded {
+            return false;
+        }
+
+        let chars = tape.get_chars_with_margin();
+        let get_index = |position: isize| (tape.get_first_char_position() + position) as usize;
+
+        if cursor_position == visit.max_position {
+            let record = EdgeRecord {
+                step: visit.step,
+                state,
+                position: cursor_position,
+                chars: chars[get_index(visit.min_position)..=get_index(cursor_position)]
+                    .iter()
+                    .rev()
+                    .copied()
+                    .collect(),
+            };
+            if is_translated_cycle(&mut self.right_records, record, &self.visits, 1) {
+                return true;
+            }
+        }
+
+        if cursor_position == visit.min_position {
+            let record = EdgeRecord {
+                step: visit.step,
+                state,
+                position: -cursor_position,
+                chars: chars[get_index(cursor_position)..=get_index(visit.max_position)].to_vec(),
+            };
+            if is_translated_cycle(&mut self.left_records, record, &self.visits, -1) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    /// Returns true if the configuration recorded at `earlier_step` is the same as `visit`,
+    /// which comes right after the last recorded one. The tapes are the same
+    /// if every cell written since then ends with the character it had before
+    fn is_same_configuration(&self, earlier_step: usize, visit: &Visit) -> bool {
+        let first_step = self.visits.first().map_or(visit.step, |visit| visit.step);
+        let earlier = &self.visits[earlier_step - first_step];
+        if earlier.state != visit.state || earlier.cursor_position != visit.cursor_position {
+            return false;
+        }
+
+        let mut cells: HashMap<isize, (char, char)> = HashMap::new();
+        let later_visits = self.visits[earlier_step - first_step + 1..].iter();
+        for (position, before, after) in later_visits.chain([visit]).filter_map(|v| v.write) {
+            cells.entry(position).or_insert((before, after)).1 = after;
+        }
+        cells.values().all(|(before, after)| before == after)
+    }
+}
+
+fn hash_cell(position: isize, character: char) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (position, character).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Saves `record` if it reaches farther than the previous ones and returns true if
+/// it repeats an earlier record of the same state moved towards the edge.
+/// Positions in `visits` are multiplied by `direction` to measure them towards the edge.
+///
+/// Between the earlier record and `record` the machine reads only cells from the lowest
+/// position visited in that time up to the edge, where all cells are blank. If `record` has
+/// the same characters in as many cells behind the cursor, the machine repeats these steps forever
+fn is_translated_cycle(
+    records: &mut Vec<EdgeRecord>,
+    record: EdgeRecord,
+    visits: &[Visit],
+    direction: isize,
+) -> bool {
+    if let Some(last) = records.last() {
+        if record.position <= last.position {
+            return false;
+        }
+    }
+
+    // Visits are recorded for every step since the first one
+    let first_step = visits.first().map_or(0, |visit| visit.step);
+    let mut min_position = record.position;
+    let mut later_step = record.step;
+
+    for earlier in records.iter().rev() {
+        for visit in &visits[earlier.step - first_step..later_step - first_step] {
+            min_position = min_position.min(visit.cursor_position * direction);
+        }
+        later_step = earlier.step;
+
+        if earlier.state != record.state {
+            continue;
+        }
+
+        let window_length = (earlier.position - min_position) as usize + 1;
+        let get_char = |chars: &[char], i: usize| chars.get(i).copied().unwrap_or(EMPTY_CHAR);
+        if (0..window_length).all(|i| get_char(&earlier.chars, i) == get_char(&record.chars, i)) {
+            return true;
+        }
+    }
+
+    if records.len() == MAX_EDGE_RECORDS {
+        records.remove(0);
+    }
+    records.push(record);
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{formats::bbchallenge::read_table, machine::HaltReason, Machine, Table};
+
+    use super::*;
+
+    fn start(table: &str, tape_chars: &str, mode: TapeMode) -> (Machine, Table) {
+        let mut tape = Tape::new();
+        tape.set_mode(mode);
+        tape.set_chars(tape_chars.to_string());
+
+        let mut machine = Machine::new();
+        machine.set_cycle_detection(true);
+        machine.reset(tape);
+        (machine, read_table(table).unwrap())
+    }
+
+    fn run(machine: &mut Machine, table: &Table, max_steps: usize) {
+        while !machine.is_halted() && machine.get_step() < max_steps {
+            machine.next_step(table);
+        }
+    }
+
+    #[test]
+    fn right_mover_loops_forever() {
+        let (mut machine, table) = start("1RA---", "#", TapeMode::Unbounded);
+        run(&mut machine, &table, 1000);
+
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::LoopsForever));
+        assert!(machine.get_step() < 10);
+    }
+
+    #[test]
+    fn right_mover_runs_out_of_bounded_tape() {
+        let (mut machine, table) = start("1RA---", "#", TapeMode::Bounded);
+        run(&mut machine, &table, 1000);
+
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::OutOfTape));
+    }
+
+    #[test]
+    fn bouncer_between_walls_loops_forever() {
+        // Moves over blank cells between two 1s, turning back at each of them
+        let (mut machine, table) = start("0RA1LB_0LB1RA", "1###1", TapeMode::Unbounded);
+        run(&mut machine, &table, 1000);
+
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::LoopsForever));
+    }
+
+    #[test]
+    fn growing_bouncer_is_not_halted() {
+        // Its tape grows on both ends, so it never repeats a configuration
+        let (mut machine, table) = start("1RB1LA_1LA1RB", "#", TapeMode::Unbounded);
+        run(&mut machine, &table, 10_000);
+
+        assert_eq!(machine.get_halt_reason(), None);
+    }
+
+    #[test]
+    fn busy_beaver_4_halts() {
+        let (mut machine, table) = start("1RB1LB_1LA0LC_1RZ1LD_1RD0RA", "#", TapeMode::Unbounded);
+        run(&mut machine, &table, 1000);
+
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::Accepted));
+        assert_eq!(machine.get_step(), 107);
+    }
+
+    #[test]
+    fn busy_beaver_5_is_not_halted() {
+        let (mut machine, table) = start(
+            "1RB1LC_1RC1RB_1RD0LE_1LA1LD_1RZ0LA",
+            "#",
+            TapeMode::Unbounded,
+        );
+        run(&mut machine, &table, 100_000);
+
+        assert_eq!(machine.get_halt_reason(), None);
+    }
+
+    #[test]
+    fn loop_is_found_again_after_undoing_steps() {
+        let (mut machine, table) = start("1RB0RA_1LA---", "#", TapeMode::Unbounded);
+        run(&mut machine, &table, 1000);
+        let loop_step = machine.get_step();
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::LoopsForever));
+
+        machine.go_to_step(1, &table);
+        run(&mut machine, &table, 1000);
+
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::LoopsForever));
+        assert_eq!(machine.get_step(), loop_step);
+    }
+
+    #[test]
+    fn stepping_back_and_forth_over_a_loop_finds_it_again() {
+        let (mut machine, table) = start("0RB---_0LA---", "#", TapeMode::Unbounded);
+        run(&mut machine, &table, 1000);
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::LoopsForever));
+        assert_eq!(machine.get_step(), 2);
+
+        for _ in 0..3 {
+            machine.previous_step();
+            machine.next_step(&table);
+
+            assert_eq!(machine.get_halt_reason(), Some(HaltReason::LoopsForever));
+            assert_eq!(machine.get_step(), 2);
+        }
+    }
+
+    #[test]
+    fn equal_hashes_of_different_tapes_are_not_a_loop() {
+        let mut detector = CycleDetector::new();
+        detector.reset(0, 0, &Tape::new());
+        let first = detector.visits[0];
+
+        // Both visits have the hash of the first one, but only the second one keeps its tape
+        let changed = Visit {
+            step: 1,
+            write: Some((0, EMPTY_CHAR, '1')),
+            ..first
+        };
+        let unchanged = Visit {
+            step: 1,
+            write: Some((0, EMPTY_CHAR, EMPTY_CHAR)),
+            ..first
+        };
+
+        assert!(!detector.is_same_configuration(0, &changed));
+        assert!(detector.is_same_configuration(0, &unchanged));
+    }
+}
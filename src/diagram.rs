@@ -0,0 +1,105 @@
+use std::collections::VecDeque;
+
+use crate::{constants::DEFAULT_STATE, table::Table, task::Task};
+
+/// Horizontal distance between layers of automatically placed states
+pub const LAYER_SPACING: f32 = 150.0;
+
+/// Vertical distance between automatically placed states of the same layer
+pub const STATE_SPACING: f32 = 100.0;
+
+/// Arrow of a state diagram for all cells going from one state to another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    pub from: usize,
+    pub to: usize,
+
+    /// Characters of the cells together with their tasks, in the order of table characters
+    pub cells: Vec<(char, Task)>,
+}
+
+impl Edge {
+    /// Returns `read → write, direction` labels of the cells
+    pub fn get_labels(&self) -> Vec<String> {
+        self.cells
+            .iter()
+            .map(|(read, task)| format!("{} → {}, {}", read, task.character, task.direction))
+            .collect()
+    }
+}
+
+/// Returns the edges of all filled cells of `table`, merging cells going between the same states.
+/// Edges keep the order of their first cells
+pub fn get_edges(table: &Table) -> Vec<Edge> {
+    let mut edges: Vec<Edge> = Vec::new();
+
+    for state in 0..table.get_states_number() {
+        for character in table.get_characters().chars() {
+            let task = *table.get_task(state, character).unwrap();
+            if task.is_halting_for(state, character) {
+                continue;
+            }
+
+            match edges
+                .iter_mut()
+                .find(|edge| edge.from == state && edge.to == task.state)
+            {
+                Some(edge) => edge.cells.push((character, task)),
+                None => edges.push(Edge {
+                    from: state,
+                    to: task.state,
+                    cells: vec![(character, task)],
+                }),
+            }
+        }
+    }
+
+    edges
+}
+
+/// Returns positions of all states of `table`. States without a saved position are placed
+/// in layers by their distance from DEFAULT_STATE and unreachable states are placed last
+pub fn get_state_positions(table: &Table) -> Vec<(f32, f32)> {
+    let states_number = table.get_states_number();
+    let mut layers = vec![None; states_number];
+    let mut queue = VecDeque::from([DEFAULT_STATE]);
+    layers[DEFAULT_STATE] = Some(0);
+
+    let edges = get_edges(table);
+    while let Some(state) = queue.pop_front() {
+        let layer = layers[state].unwrap();
+
+        for edge in edges.iter().filter(|edge| edge.from == state) {
+            if edge.to < states_number && layers[edge.to].is_none() {
+                layers[edge.to] = Some(layer + 1);
+                queue.push_back(edge.to);
+            }
+        }
+    }
+
+    let unreachable_layer = layers.iter().flatten().max().unwrap() + 1;
+    let layers: Vec<usize> = layers
+        .into_iter()
+        .map(|layer| layer.unwrap_or(unreachable_layer))
+        .collect();
+
+    let mut layer_sizes = vec![0; unreachable_layer + 1];
+    for &layer in &layers {
+        layer_sizes[layer] += 1;
+    }
+
+    // Layers are centered vertically around 0
+    let mut indexes_in_layers = vec![0; unreachable_layer + 1];
+    (0..states_number)
+        .map(|state| {
+            let layer = layers[state];
+            let index = indexes_in_layers[layer];
+            indexes_in_layers[layer] += 1;
+
+            table.get_state_position(state).unwrap_or((
+                layer as f32 * LAYER_SPACING,
+                (index as f32 - (layer_sizes[layer] - 1) as f32 / 2.0) * STATE_SPACING,
+            ))
+        })
+        .collect()
+}
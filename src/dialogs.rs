@@ -2,25 +2,103 @@ use std::path::PathBuf;
 
 use iced::Command;
 use rfd::{FileDialog, MessageButtons, MessageDialog, MessageLevel};
+use turing_machine::{
+    constants::{
+        BBCHALLENGE_FILE_EXTENSION, DOT_FILE_EXTENSION, FILE_EXTENSION, GV_FILE_EXTENSION,
+        JFLAP_FILE_EXTENSION, JSON_FILE_EXTENSION, PROJECT_FILE_EXTENSION, YAML_FILE_EXTENSION,
+        YML_FILE_EXTENSION,
+    },
+    table::ExpectedToken,
+    ParseError,
+};
 
-use crate::{constants::FILE_EXTENSION, language::Language, Message};
+use crate::{
+    gui_constants::{GIF_FILE_EXTENSION, PNG_FILE_EXTENSION},
+    language::Language,
+    Message,
+};
+
+/// Extensions of all files which can be opened and saved
+const ALL_FILE_EXTENSIONS: [&str; 7] = [
+    PROJECT_FILE_EXTENSION,
+    FILE_EXTENSION,
+    JSON_FILE_EXTENSION,
+    JFLAP_FILE_EXTENSION,
+    YAML_FILE_EXTENSION,
+    YML_FILE_EXTENSION,
+    BBCHALLENGE_FILE_EXTENSION,
+];
 
 #[derive(PartialEq, Eq)]
 pub struct DialogTexsts {
     pub open_file_error_description: &'static str,
     pub save_file_error_description: &'static str,
+    pub export_file_error_description: &'static str,
+    pub add_test_error_description: &'static str,
     pub error_message_title: &'static str,
     pub unsaved_file_dialog_title: &'static str,
     pub unsaved_file_dialog_description: &'static str,
+    pub lossy_file_dialog_title: &'static str,
+    pub lossy_file_dialog_description: &'static str,
     pub file_filter_name: &'static str,
+    pub project_file_filter_name: &'static str,
+    pub table_file_filter_name: &'static str,
+    pub json_file_filter_name: &'static str,
+    pub jflap_file_filter_name: &'static str,
+    pub yaml_file_filter_name: &'static str,
+    pub bbchallenge_file_filter_name: &'static str,
+    pub dot_file_filter_name: &'static str,
+    pub png_file_filter_name: &'static str,
+    pub gif_file_filter_name: &'static str,
+    pub parse_error_texts: ParseErrorTexts,
 }
 
-pub fn error_dialog(description: &'static str, language: &'static Language) -> Command<Message> {
-    async fn a(description: &str, language: &'static Language) {
+#[derive(PartialEq, Eq)]
+pub struct ParseErrorTexts {
+    pub line_label: &'static str,
+    pub column_label: &'static str,
+    pub expected_label: &'static str,
+    pub found_label: &'static str,
+    pub end_of_line_text: &'static str,
+    pub state_number_text: &'static str,
+    pub character_text: &'static str,
+    pub direction_text: &'static str,
+    pub state_kind_marker_text: &'static str,
+}
+
+/// Describes where and why reading a file failed in the language of the app
+pub fn describe_parse_error(parse_error: &ParseError, language: &'static Language) -> String {
+    let texts = &language.dialog_texts.parse_error_texts;
+    let expected = match parse_error.expected {
+        ExpectedToken::StateNumber => texts.state_number_text,
+        ExpectedToken::Character => texts.character_text,
+        ExpectedToken::Direction => texts.direction_text,
+        ExpectedToken::StateKindMarker => texts.state_kind_marker_text,
+    };
+    let found = match parse_error.token.is_empty() {
+        true => texts.end_of_line_text.to_string(),
+        false => format!("\"{}\"", parse_error.token),
+    };
+
+    format!(
+        "{} {}, {} {}: {} {}, {} {}",
+        texts.line_label,
+        parse_error.line,
+        texts.column_label,
+        parse_error.column,
+        texts.expected_label,
+        expected,
+        texts.found_label,
+        found
+    )
+}
+
+pub fn error_dialog(description: String, language: &'static Language) -> Command<Message> {
+    async fn a(description: String, language: &'static Language) {
         MessageDialog::new()
             .set_level(MessageLevel::Error)
             .set_title(language.dialog_texts.error_message_title)
-            .set_description(description)
+            .set_description(&description)
             .set_buttons(MessageButtons::Ok)
             .show();
     }
@@ -45,10 +123,38 @@ pub fn unsaved_file_dialog(
     return Command::perform(a(callback, language), Message::UnsavedFileDialogAnsweared);
 }
 
+/// Asks whether to save the project to `path` in a format which drops its tests and metadata.
+/// `callback` is sent after saving, e.g. to continue the action which asked for saving
+pub fn lossy_file_dialog(
+    path: PathBuf,
+    callback: Option<Box<Message>>,
+    language: &'static Language,
+) -> Command<Message> {
+    async fn a(
+        path: PathBuf,
+        callback: Option<Box<Message>>,
+        language: &'static Language,
+    ) -> (bool, PathBuf, Option<Box<Message>>) {
+        let choice = MessageDialog::new()
+            .set_level(MessageLevel::Warning)
+            .set_title(language.dialog_texts.lossy_file_dialog_title)
+            .set_description(language.dialog_texts.lossy_file_dialog_description)
+            .set_buttons(rfd::MessageButtons::YesNo)
+            .show();
+
+        (choice, path, callback)
+    }
+
+    Command::perform(
+        a(path, callback, language),
+        Message::LossyFileDialogAnsweared,
+    )
+}
+
 pub fn pick_file_to_open_dialog(language: &'static Language) -> Command<Message> {
     async fn a(language: &'static Language) -> Option<PathBuf> {
         FileDialog::new()
-            .add_filter(language.dialog_texts.file_filter_name, &[FILE_EXTENSION])
+            .add_filter(language.dialog_texts.file_filter_name, &ALL_FILE_EXTENSIONS)
             .pick_file()
     }
 
@@ -57,17 +163,41 @@ pub fn pick_file_to_open_dialog(language: &'static Language) -> Command<Message>
 
 pub fn pick_file_to_save_dialog(language: &'static Language) -> Command<Message> {
     async fn a(language: &'static Language) -> Option<PathBuf> {
+        // JSON is the first choice, because only it keeps tests and metadata
         let path = FileDialog::new()
-            .add_filter(language.dialog_texts.file_filter_name, &[FILE_EXTENSION])
+            .add_filter(
+                language.dialog_texts.json_file_filter_name,
+                &[JSON_FILE_EXTENSION],
+            )
+            .add_filter(
+                language.dialog_texts.project_file_filter_name,
+                &[PROJECT_FILE_EXTENSION],
+            )
+            .add_filter(
+                language.dialog_texts.table_file_filter_name,
+                &[FILE_EXTENSION],
+            )
+            .add_filter(
+                language.dialog_texts.jflap_file_filter_name,
+                &[JFLAP_FILE_EXTENSION],
+            )
+            .add_filter(
+                language.dialog_texts.yaml_file_filter_name,
+                &[YAML_FILE_EXTENSION, YML_FILE_EXTENSION],
+            )
+            .add_filter(
+                language.dialog_texts.bbchallenge_file_filter_name,
+                &[BBCHALLENGE_FILE_EXTENSION],
+            )
             .set_file_name(language.default_filename)
             .save_file();
 
         if let Some(mut path) = path {
             match path.extension() {
-                Some(ext) if ext == FILE_EXTENSION => {}
+                Some(ext) if ALL_FILE_EXTENSIONS.iter().any(|e| ext == *e) => {}
                 _ => {
                     let new_filename =
-                        format!("{}.{}", path.file_name()?.to_str()?, FILE_EXTENSION);
+                        format!("{}.{}", path.file_name()?.to_str()?, JSON_FILE_EXTENSION);
                     path.set_file_name(new_filename);
                 }
             }
@@ -81,6 +211,58 @@ pub fn pick_file_to_save_dialog(language: &'static Language) -> Command<Message>
     return Command::perform(a(language), Message::FileToSavePicked);
 }
 
+pub fn pick_state_diagram_file_to_export_dialog(language: &'static Language) -> Command<Message> {
+    pick_file_to_export_dialog(
+        language.dialog_texts.dot_file_filter_name,
+        &[DOT_FILE_EXTENSION, GV_FILE_EXTENSION],
+        Message::StateDiagramFileToExportPicked,
+    )
+}
+
+pub fn pick_space_time_diagram_file_to_export_dialog(
+    language: &'static Language,
+) -> Command<Message> {
+    pick_file_to_export_dialog(
+        language.dialog_texts.png_file_filter_name,
+        &[PNG_FILE_EXTENSION],
+        Message::SpaceTimeDiagramFileToExportPicked,
+    )
+}
+
+pub fn pick_animation_file_to_export_dialog(language: &'static Language) -> Command<Message> {
+    pick_file_to_export_dialog(
+        language.dialog_texts.gif_file_filter_name,
+        &[GIF_FILE_EXTENSION],
+        Message::AnimationFileToExportPicked,
+    )
+}
+
+/// Asks where to export a file and appends the first of `extensions`
+/// to the picked path if it has none of them
+fn pick_file_to_export_dialog(
+    filter_name: &'static str,
+    extensions: &'static [&'static str],
+    on_picked: fn(Option<PathBuf>) -> Message,
+) -> Command<Message> {
+    async fn a(filter_name: &'static str, extensions: &'static [&'static str]) -> Option<PathBuf> {
+        let mut path = FileDialog::new()
+            .add_filter(filter_name, extensions)
+            .save_file()?;
+
+        match path.extension() {
+            Some(ext) if extensions.iter().any(|e| ext == *e) => {}
+            _ => {
+                let new_filename = format!("{}.{}", path.file_name()?.to_str()?, extensions[0]);
+                path.set_file_name(new_filename);
+            }
+        }
+
+        return Some(path);
+    }
+
+    return Command::perform(a(filter_name, extensions), on_picked);
+}
+
 #[derive(PartialEq, Eq)]
 pub struct AboutProgramDialogLabels {
     pub program_name_label: &'static str,
@@ -0,0 +1,400 @@
+//! Standard text notation of busy beaver machines used by bbchallenge,
+//! e.g. `1RB1LC_1RC1RB_1RD0LE_1LA1LD_1RZ0LA`.
+//!
+//! States are separated by `_` and named with letters, so A becomes state 0,
+//! B state 1 and so on. Every state has one transition for each symbol, made of
+//! the written digit, the move (`L` or `R`) and the next state. Symbol 0 is blank,
+//! so it is mapped to EMPTY_CHAR and other symbols are mapped to their digits.
+//! `---` means that there is no transition, so it becomes a cell which halts the machine
+//! without changing the tape. `Z`, like any other state which doesn't exist, becomes
+//! an extra accepting state added after the others, so the machine halts after writing.
+//!
+//! When writing, characters have to be EMPTY_CHAR or digits from 1 to 9, which keep their
+//! symbols, and symbols missing from the table get `---`. Accepting states become `Z`
+//! and tasks with Direction::Stop which stay in their state are written as moves to
+//! the right into `Z`. Rejecting states and stopping tasks which change the state
+//! can't be represented, so they are rejected.
+
+use std::io::{prelude::*, Error, ErrorKind};
+
+use crate::{
+    constants::EMPTY_CHAR,
+    project::Project,
+    table::{StateKind, Table},
+    tape::{Tape, TapeMode},
+    task::{Direction, Task},
+};
+
+const STATES_SEPARATOR: char = '_';
+const UNDEFINED_TRANSITION: &str = "---";
+const HALTING_STATE: char = 'Z';
+const FIRST_STATE: char = 'A';
+const MAX_SYMBOLS_NUMBER: usize = 10;
+
+/// Every state except Z needs its own letter
+const MAX_STATES_NUMBER: usize = 25;
+
+pub fn read_project(buffer: &mut impl Read) -> Result<Project, Error> {
+    let mut text = String::new();
+    buffer.read_to_string(&mut text)?;
+
+    // Busy beavers run on an infinite tape
+    let mut tape = Tape::new();
+    tape.set_mode(TapeMode::Unbounded);
+
+    Ok(Project {
+        table: read_table(text.trim())?,
+        tape,
+        ..Project::new_empty()
+    })
+}
+
+pub fn write_project(project: &Project, buffer: &mut impl Write) -> Result<(), Error> {
+    writeln!(buffer, "{}", write_table(&project.table)?)
+}
+
+pub fn read_table(text: &str) -> Result<Table, Error> {
+    let rows: Vec<Vec<char>> = text
+        .split(STATES_SEPARATOR)
+        .map(|row| row.chars().collect())
+        .collect();
+    let symbols_number = rows[0].len() / 3;
+
+    if rows.len() > MAX_STATES_NUMBER
+        || !(1..=MAX_SYMBOLS_NUMBER).contains(&symbols_number)
+        || rows.iter().any(|row| row.len() != symbols_number * 3)
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "every state has to have the same number of transitions",
+        ));
+    }
+
+    let symbols: Vec<char> = (0..symbols_number).map(symbol_to_char).collect();
+    let mut tasks = Vec::new();
+
+    for (state, row) in rows.iter().enumerate() {
+        for (transition, &read) in row.chunks(3).zip(&symbols) {
+            let task =
+                parse_transition(transition, symbols_number, rows.len()).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "invalid transition {} of state {}",
+                            transition.iter().collect::<String>(),
+                            state_to_letter(state)
+                        ),
+                    )
+                })?;
+
+            tasks.push((state, read, task));
+        }
+    }
+
+    // The halting state is added only if some transition goes to it
+    let halting_state = rows.len();
+    let is_halting_state_used = tasks.iter().any(|(_, _, task)| task.state == halting_state);
+
+    let mut table = Table::new_empty();
+    table.set_characters(&symbols.iter().collect::<String>());
+    table.set_states_number(rows.len() + is_halting_state_used as usize);
+    if is_halting_state_used {
+        table.set_state_kind(StateKind::Accepting, halting_state);
+        table.set_state_name(HALTING_STATE.to_string(), halting_state);
+    }
+
+    for (state, read, task) in tasks {
+        table.set_task_by_state_and_character(task, state, read);
+    }
+
+    Ok(table)
+}
+
+pub fn write_table(table: &Table) -> Result<String, Error> {
+    let invalid_data_error = |message: &str| Error::new(ErrorKind::InvalidData, message);
+
+    // Every character keeps its own digit, so symbols missing from the table get no transitions
+    let mut symbols_number = 1;
+    for c in table.get_characters().chars() {
+        let symbol = char_to_symbol(c).ok_or_else(|| {
+            invalid_data_error("only EMPTY_CHAR and digits from 1 to 9 can be written")
+        })?;
+        symbols_number = symbols_number.max(symbol + 1);
+    }
+    let symbols: Vec<char> = (0..symbols_number).map(symbol_to_char).collect();
+
+    // Normal states get letters in their order and accepting states become the halting state
+    let mut letters = Vec::with_capacity(table.get_states_number());
+    let mut normal_states = Vec::new();
+    for state in 0..table.get_states_number() {
+        let letter = match table.get_state_kind(state) {
+            StateKind::Normal if normal_states.len() == MAX_STATES_NUMBER => {
+                return Err(invalid_data_error("the table has too many states"))
+            }
+            StateKind::Normal => {
+                normal_states.push(state);
+                state_to_letter(normal_states.len() - 1)
+            }
+            StateKind::Accepting => HALTING_STATE,
+            StateKind::Rejecting => {
+                return Err(invalid_data_error("rejecting states can't be written"))
+            }
+        };
+        letters.push(letter);
+    }
+
+    if normal_states.first() != Some(&0) {
+        return Err(invalid_data_error(
+            "the first state has to be a normal state",
+        ));
+    }
+
+    let mut rows = Vec::with_capacity(normal_states.len());
+    for state in normal_states {
+        let mut row = String::new();
+
+        for &read in &symbols {
+            let task = match table.get_task(state, read) {
+                Some(task) if !task.is_halting_for(state, read) => task,
+                _ => {
+                    row += UNDEFINED_TRANSITION;
+                    continue;
+                }
+            };
+
+            let written_symbol = char_to_symbol(task.character)
+                .filter(|symbol| *symbol < symbols_number)
+                .ok_or_else(|| invalid_data_error("the table writes unknown characters"))?;
+
+            // Going to a state which doesn't exist halts the machine as well
+            let next_state = letters.get(task.state).copied().unwrap_or(HALTING_STATE);
+            let (direction, next_state) = match task.direction {
+                Direction::Left => ('L', next_state),
+                Direction::Right => ('R', next_state),
+                Direction::Stop if task.state == state => ('R', HALTING_STATE),
+                Direction::Stop => {
+                    return Err(invalid_data_error(
+                        "the table has stopping tasks which change the state",
+                    ))
+                }
+            };
+
+            row.push_str(&format!("{}{}{}", written_symbol, direction, next_state));
+        }
+
+        rows.push(row);
+    }
+
+    Ok(rows.join(&STATES_SEPARATOR.to_string()))
+}
+
+fn parse_transition(
+    transition: &[char],
+    symbols_number: usize,
+    states_number: usize,
+) -> Option<Task> {
+    if transition.iter().collect::<String>() == UNDEFINED_TRANSITION {
+        return Some(Task::new());
+    }
+
+    let written_symbol = transition[0].to_digit(10)? as usize;
+    if written_symbol >= symbols_number {
+        return None;
+    }
+
+    let direction = match transition[1] {
+        'L' => Direction::Left,
+        'R' => Direction::Right,
+        _ => return None,
+    };
+
+    let next_state = letter_to_state(transition[2])?;
+    let character = symbol_to_char(written_symbol);
+
+    // Going to a state which doesn't exist halts the machine in the halting state,
+    // which comes after all other states
+    Some(Task {
+        state: next_state.min(states_number),
+        character,
+        direction,
+    })
+}
+
+fn symbol_to_char(symbol: usize) -> char {
+    match symbol {
+        0 => EMPTY_CHAR,
+        symbol => char::from_digit(symbol as u32, 10).unwrap(),
+    }
+}
+
+fn char_to_symbol(c: char) -> Option<usize> {
+    match c {
+        EMPTY_CHAR => Some(0),
+        '1'..='9' => c.to_digit(10).map(|digit| digit as usize),
+        _ => None,
+    }
+}
+
+fn letter_to_state(letter: char) -> Option<usize> {
+    match letter {
+        'A'..='Z' => Some(letter as usize - FIRST_STATE as usize),
+        _ => None,
+    }
+}
+
+/// States which don't have their own letter are written as the halting state
+fn state_to_letter(state: usize) -> char {
+    match state < MAX_STATES_NUMBER {
+        true => char::from_u32(FIRST_STATE as u32 + state as u32).unwrap(),
+        false => HALTING_STATE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{machine::HaltReason, Machine};
+
+    use super::*;
+
+    fn round_trip(text: &str) -> String {
+        write_table(&read_table(text).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn round_trip_keeps_halting_transitions() {
+        assert_eq!(round_trip("1RB1LB_1LA1RZ"), "1RB1LB_1LA1RZ");
+        assert_eq!(round_trip("1RB0RZ_1LA1LB"), "1RB0RZ_1LA1LB");
+    }
+
+    #[test]
+    fn round_trip_keeps_undefined_transitions() {
+        assert_eq!(round_trip("1RB0RA_1LA---"), "1RB0RA_1LA---");
+    }
+
+    #[test]
+    fn round_trip_keeps_busy_beaver_5() {
+        let text = "1RB1LC_1RC1RB_1RD0LE_1LA1LD_1RZ0LA";
+        assert_eq!(round_trip(text), text);
+    }
+
+    fn run(table: &Table) -> Machine {
+        let mut tape = Tape::new();
+        tape.set_mode(TapeMode::Unbounded);
+        let mut machine = Machine::new();
+        machine.reset(tape);
+        while !machine.is_halted() {
+            machine.next_step(table);
+        }
+        machine
+    }
+
+    fn tape_text(machine: &Machine) -> String {
+        let chars: String = machine.get_tape().get_chars_with_margin().iter().collect();
+        chars.trim_matches(EMPTY_CHAR).to_string()
+    }
+
+    #[test]
+    fn halting_transition_goes_to_accepting_state() {
+        let table = read_table("1RB1LB_1LA1RZ").unwrap();
+        assert_eq!(table.get_states_number(), 3);
+        assert_eq!(table.get_state_kind(2), StateKind::Accepting);
+        assert_eq!(
+            table.get_task(1, '1'),
+            Some(&Task {
+                state: 2,
+                character: '1',
+                direction: Direction::Right
+            })
+        );
+
+        let machine = run(&table);
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::Accepted));
+        assert_eq!(machine.get_step(), 6);
+        assert_eq!(tape_text(&machine), "1111");
+    }
+
+    #[test]
+    fn undefined_transition_writes_nothing() {
+        let table = read_table("1RB---_---1LA").unwrap();
+        assert!(table
+            .get_task(1, EMPTY_CHAR)
+            .unwrap()
+            .is_halting_for(1, EMPTY_CHAR));
+
+        let machine = run(&table);
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::NoRule));
+        assert_eq!(tape_text(&machine), "1");
+    }
+
+    #[test]
+    fn stopping_task_changing_state_is_rejected() {
+        let mut table = read_table("1RB1LB_1LA1RZ").unwrap();
+        table.set_task_by_state_and_character(
+            Task {
+                state: 0,
+                character: '1',
+                direction: Direction::Stop,
+            },
+            1,
+            '1',
+        );
+
+        let error = write_table(&table).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn stopping_task_staying_in_state_goes_to_z() {
+        let mut table = read_table("1RB1LB_1LA1RZ").unwrap();
+        table.set_task_by_state_and_character(Task::new_halting(1, EMPTY_CHAR), 1, '1');
+
+        assert_eq!(write_table(&table).unwrap(), "1RB1LB_1LA0RZ");
+    }
+
+    #[test]
+    fn rejecting_state_is_rejected() {
+        let mut table = read_table("1RB1LB_1LA1RZ").unwrap();
+        table.set_state_kind(StateKind::Rejecting, 2);
+
+        assert!(write_table(&table).is_err());
+    }
+
+    fn table_with_characters(characters: &str) -> Table {
+        let mut table = Table::new_empty();
+        table.set_states_number(1);
+        table.set_characters(characters);
+        for c in characters.chars() {
+            let task = Task {
+                state: 0,
+                character: c,
+                direction: Direction::Right,
+            };
+            table.set_task_by_state_and_character(task, 0, c);
+        }
+        table
+    }
+
+    #[test]
+    fn characters_keep_their_digits() {
+        assert_eq!(
+            write_table(&table_with_characters("#21")).unwrap(),
+            "0RA1RA2RA"
+        );
+        assert_eq!(
+            write_table(&table_with_characters("#2")).unwrap(),
+            "0RA---2RA"
+        );
+    }
+
+    #[test]
+    fn characters_other_than_digits_are_rejected() {
+        let error = write_table(&table_with_characters("#ab")).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn uneven_rows_are_rejected() {
+        let error = read_table("1RB1LB_1LA").err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
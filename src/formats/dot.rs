@@ -0,0 +1,140 @@
+//! State diagrams in the DOT language of Graphviz (.dot and .gv files),
+//! which can only be saved.
+//!
+//! Every state is a node and every filled cell is an edge labelled
+//! `read → write, direction`. Cells going between the same states share one edge.
+//! The start state has a bold border and an incoming arrow. States which don't
+//! exist in the table, but are used by tasks, have dashed borders.
+
+use std::io::{prelude::*, Error};
+
+use crate::{
+    constants::DEFAULT_STATE,
+    diagram::get_edges,
+    project::Project,
+    table::{StateKind, Table},
+};
+
+const START_NODE_ID: &str = "start";
+
+pub fn write_project(project: &Project, buffer: &mut impl Write) -> Result<(), Error> {
+    write_table(&project.table, &project.metadata.name, buffer)
+}
+
+/// Writes the state diagram of `table` as a graph called `name`
+pub fn write_table(table: &Table, name: &str, buffer: &mut impl Write) -> Result<(), Error> {
+    let edges = get_edges(table);
+    let states_number = edges
+        .iter()
+        .map(|edge| edge.to + 1)
+        .fold(table.get_states_number(), usize::max);
+
+    writeln!(buffer, "digraph {} {{", quote(name))?;
+    writeln!(buffer, "\trankdir=LR;")?;
+    writeln!(buffer, "\tnode [shape=circle];")?;
+    writeln!(buffer, "\t{} [shape=point];", START_NODE_ID)?;
+
+    for state in 0..states_number {
+        let label = match table.get_state_name(state) {
+            "" => state.to_string(),
+            name => name.to_string(),
+        };
+
+        let mut attributes = vec![format!("label={}", quote(&label))];
+        if state >= table.get_states_number() {
+            attributes.push("style=dashed".to_string());
+        } else {
+            match table.get_state_kind(state) {
+                StateKind::Normal => {}
+                StateKind::Accepting => attributes.push("shape=doublecircle".to_string()),
+                StateKind::Rejecting => attributes.push("shape=octagon".to_string()),
+            }
+        }
+        if state == DEFAULT_STATE {
+            attributes.push("penwidth=2".to_string());
+        }
+
+        writeln!(buffer, "\t{} [{}];", state, attributes.join(", "))?;
+    }
+
+    writeln!(buffer, "\t{} -> {};", START_NODE_ID, DEFAULT_STATE)?;
+
+    for edge in edges {
+        writeln!(
+            buffer,
+            "\t{} -> {} [label={}];",
+            edge.from,
+            edge.to,
+            quote(&edge.get_labels().join("\n"))
+        )?;
+    }
+
+    writeln!(buffer, "}}")
+}
+
+fn quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constants::EMPTY_CHAR,
+        task::{Direction, Task},
+    };
+
+    use super::*;
+
+    fn task(state: usize, character: char, direction: Direction) -> Task {
+        Task {
+            state,
+            character,
+            direction,
+        }
+    }
+
+    #[test]
+    fn edges_and_labels_are_written_and_escaped() {
+        let mut table = Table::new_empty();
+        table.set_characters("#\"\\");
+        table.set_states_number(2);
+        let tasks = [
+            (0, EMPTY_CHAR, task(1, '"', Direction::Right)),
+            (0, '"', task(1, '\\', Direction::Left)),
+            (1, '\\', task(2, EMPTY_CHAR, Direction::Stop)),
+        ];
+        for (state, read, task) in tasks {
+            table.set_task_by_state_and_character(task, state, read);
+        }
+        table.set_state_name("say \"hi\"\\".to_string(), 0);
+        table.set_state_kind(StateKind::Accepting, 1);
+
+        let mut buffer = Vec::new();
+        write_table(&table, "a \"b\"", &mut buffer).unwrap();
+
+        let expected = [
+            "digraph \"a \\\"b\\\"\" {",
+            "\trankdir=LR;",
+            "\tnode [shape=circle];",
+            "\tstart [shape=point];",
+            "\t0 [label=\"say \\\"hi\\\"\\\\\", penwidth=2];",
+            "\t1 [label=\"1\", shape=doublecircle];",
+            "\t2 [label=\"2\", style=dashed];",
+            "\tstart -> 0;",
+            "\t0 -> 1 [label=\"# → \\\", +\\n\\\" → \\\\, -\"];",
+            "\t1 -> 2 [label=\"\\\\ → #, 0\"];",
+            "}",
+        ];
+        assert_eq!(
+            String::from_utf8(buffer)
+                .unwrap()
+                .lines()
+                .collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
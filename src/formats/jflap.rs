@@ -0,0 +1,373 @@
+//! Single-tape Turing machines of JFLAP (.jff files).
+//!
+//! The initial state becomes state 0 and the other states keep their order.
+//! Final states become accepting states. The blank symbol, which JFLAP writes
+//! as an empty element or `□`, is mapped to EMPTY_CHAR. Cells without a transition
+//! halt the machine without changing the tape, just like missing transitions in JFLAP.
+//!
+//! The `S` move of JFLAP stays in place and keeps running, which a table can't do,
+//! so files with it are rejected. Tasks with Direction::Stop which change the tape
+//! or the state are written as moves to the right into an extra state without transitions.
+
+use std::{
+    collections::HashSet,
+    io::{prelude::*, Error, ErrorKind},
+};
+
+use roxmltree::{Document, Node};
+
+use crate::{
+    constants::{EMPTY_CHAR, MAX_STATES_NUMBER},
+    project::Project,
+    table::{single_char, StateKind, Table},
+    tape::{Tape, TapeMode},
+    task::{Direction, Task},
+};
+
+const JFLAP_BLANK: char = '□';
+const TURING_MACHINE_TYPE: &str = "turing";
+const STAY_MOVE: &str = "S";
+const STOP_STATE_NAME: &str = "stop";
+
+/// Distance between states placed on the JFLAP canvas
+const STATE_SPACING: usize = 150;
+const STATES_PER_ROW: usize = 8;
+
+pub fn read_project(buffer: &mut impl Read) -> Result<Project, Error> {
+    let invalid_data_error = || Error::from(ErrorKind::InvalidData);
+    let mut xml = String::new();
+    buffer.read_to_string(&mut xml)?;
+
+    let document = Document::parse(&xml).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let structure = document.root_element();
+
+    if child_text(structure, "type") != Some(TURING_MACHINE_TYPE) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "the file doesn't contain a Turing machine",
+        ));
+    }
+
+    if !matches!(child_text(structure, "tapes"), None | Some("1")) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "only single-tape Turing machines are supported",
+        ));
+    }
+
+    // Older versions of JFLAP put states and transitions directly in the structure
+    let automaton = child(structure, "automaton").unwrap_or(structure);
+    let mut state_nodes: Vec<Node> = children(automaton, "state").collect();
+    let initial_position = state_nodes
+        .iter()
+        .position(|node| child(*node, "initial").is_some())
+        .ok_or(invalid_data_error())?;
+
+    // The machine always starts in state 0
+    let initial_node = state_nodes.remove(initial_position);
+    state_nodes.insert(0, initial_node);
+
+    if state_nodes.len() > MAX_STATES_NUMBER {
+        return Err(invalid_data_error());
+    }
+
+    let ids: Vec<&str> = state_nodes
+        .iter()
+        .map(|node| node.attribute("id").ok_or(invalid_data_error()))
+        .collect::<Result<_, _>>()?;
+    let state_index = |id: Option<&str>| {
+        ids.iter()
+            .position(|i| Some(*i) == id)
+            .ok_or(invalid_data_error())
+    };
+
+    let mut tasks = Vec::new();
+    for transition in children(automaton, "transition") {
+        let from = state_index(child_text(transition, "from"))?;
+        let to = state_index(child_text(transition, "to"))?;
+        let read = parse_symbol(child_text(transition, "read")).ok_or(invalid_data_error())?;
+        let write = parse_symbol(child_text(transition, "write")).ok_or(invalid_data_error())?;
+        let direction = match child_text(transition, "move") {
+            Some("R") => Direction::Right,
+            Some("L") => Direction::Left,
+            Some(STAY_MOVE) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "the S move (staying in place) isn't supported",
+                ))
+            }
+            _ => return Err(invalid_data_error()),
+        };
+
+        tasks.push((
+            from,
+            read,
+            Task {
+                state: to,
+                character: write,
+                direction,
+            },
+        ));
+    }
+
+    let mut characters = EMPTY_CHAR.to_string();
+    for (_, read, task) in &tasks {
+        for c in [*read, task.character] {
+            if !characters.contains(c) {
+                characters.push(c);
+            }
+        }
+    }
+
+    let mut table = Table::new_empty();
+    table.set_characters(&characters);
+    table.set_states_number(state_nodes.len());
+
+    for (state, node) in state_nodes.iter().enumerate() {
+        table.set_state_name(node.attribute("name").unwrap_or("").to_string(), state);
+        if child(*node, "final").is_some() {
+            table.set_state_kind(StateKind::Accepting, state);
+        }
+    }
+
+    // Only deterministic machines can be represented by a table
+    let mut filled_cells = HashSet::new();
+    for (state, read, task) in tasks {
+        if !filled_cells.insert((state, read)) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "the Turing machine is not deterministic",
+            ));
+        }
+
+        table.set_task_by_state_and_character(task, state, read);
+    }
+
+    // The tape of JFLAP is infinite in both directions
+    let mut tape = Tape::new();
+    tape.set_mode(TapeMode::Unbounded);
+
+    Ok(Project {
+        table,
+        tape,
+        ..Project::new_empty()
+    })
+}
+
+pub fn write_project(project: &Project, buffer: &mut impl Write) -> Result<(), Error> {
+    let table = &project.table;
+    let mut transitions = Vec::new();
+
+    for state in 0..table.get_states_number() {
+        for character in table.get_characters().chars() {
+            let task = table.get_task(state, character).unwrap();
+            if !task.is_halting_for(state, character) {
+                transitions.push((state, character, *task));
+            }
+        }
+    }
+
+    // JFLAP requires every transition to go to an existing state,
+    // so states missing in the table are added without any transitions
+    let states_number = transitions
+        .iter()
+        .map(|(_, _, task)| task.state + 1)
+        .fold(table.get_states_number(), usize::max);
+
+    // Stopping tasks go to an extra state, which halts the machine
+    let is_stop_state_used = transitions
+        .iter()
+        .any(|(_, _, task)| task.direction == Direction::Stop);
+    let stop_state = states_number;
+
+    writeln!(
+        buffer,
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#
+    )?;
+    writeln!(buffer, "<structure>")?;
+    writeln!(buffer, "\t<type>{}</type>", TURING_MACHINE_TYPE)?;
+    writeln!(buffer, "\t<automaton>")?;
+
+    for state in 0..states_number + is_stop_state_used as usize {
+        let name = match table.get_state_name(state) {
+            _ if state == stop_state => STOP_STATE_NAME.to_string(),
+            "" => format!("q{}", state),
+            name => name.to_string(),
+        };
+
+        writeln!(
+            buffer,
+            r#"		<state id="{}" name="{}">"#,
+            state,
+            escape(&name)
+        )?;
+        writeln!(
+            buffer,
+            "\t\t\t<x>{}</x>",
+            (state % STATES_PER_ROW + 1) * STATE_SPACING
+        )?;
+        writeln!(
+            buffer,
+            "\t\t\t<y>{}</y>",
+            (state / STATES_PER_ROW + 1) * STATE_SPACING
+        )?;
+        if state == 0 {
+            writeln!(buffer, "\t\t\t<initial/>")?;
+        }
+        // JFLAP has no rejecting states, so they are written as normal ones
+        if table.get_state_kind(state) == StateKind::Accepting {
+            writeln!(buffer, "\t\t\t<final/>")?;
+        }
+        writeln!(buffer, "\t\t</state>")?;
+    }
+
+    for (state, character, task) in transitions {
+        let (direction, next_state) = match task.direction {
+            Direction::Left => ("L", task.state),
+            Direction::Right => ("R", task.state),
+            Direction::Stop => ("R", stop_state),
+        };
+
+        writeln!(buffer, "\t\t<transition>")?;
+        writeln!(buffer, "\t\t\t<from>{}</from>", state)?;
+        writeln!(buffer, "\t\t\t<to>{}</to>", next_state)?;
+        writeln!(buffer, "\t\t\t{}", symbol_element("read", character))?;
+        writeln!(buffer, "\t\t\t{}", symbol_element("write", task.character))?;
+        writeln!(buffer, "\t\t\t<move>{}</move>", direction)?;
+        writeln!(buffer, "\t\t</transition>")?;
+    }
+
+    writeln!(buffer, "\t</automaton>")?;
+    writeln!(buffer, "</structure>")
+}
+
+/// Returns the character of a symbol element, which is blank if the element is empty
+fn parse_symbol(text: Option<&str>) -> Option<char> {
+    match text {
+        None | Some("") => Some(EMPTY_CHAR),
+        Some(text) => match single_char(text)? {
+            JFLAP_BLANK => Some(EMPTY_CHAR),
+            c => Some(c),
+        },
+    }
+}
+
+fn symbol_element(name: &str, character: char) -> String {
+    match character {
+        EMPTY_CHAR => format!("<{}/>", name),
+        c => format!("<{}>{}</{}>", name, escape(&c.to_string()), name),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.chars().fold(String::new(), |mut acc, c| {
+        match c {
+            '&' => acc.push_str("&amp;"),
+            '<' => acc.push_str("&lt;"),
+            '>' => acc.push_str("&gt;"),
+            '"' => acc.push_str("&quot;"),
+            '\'' => acc.push_str("&apos;"),
+            c => acc.push(c),
+        }
+        acc
+    })
+}
+
+fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+    name: &'a str,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(move |n| n.has_tag_name(name))
+}
+
+fn child<'a, 'input>(node: Node<'a, 'input>, name: &'a str) -> Option<Node<'a, 'input>> {
+    children(node, name).next()
+}
+
+/// Returns the trimmed text of the first child element called `name`
+fn child_text<'a>(node: Node<'a, '_>, name: &'a str) -> Option<&'a str> {
+    child(node, name).map(|n| n.text().unwrap_or("").trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(state: usize, character: char, direction: Direction) -> Task {
+        Task {
+            state,
+            character,
+            direction,
+        }
+    }
+
+    fn write_and_read(table: Table) -> Table {
+        let project = Project {
+            table,
+            ..Project::new_empty()
+        };
+        let mut buffer = Vec::new();
+        write_project(&project, &mut buffer).unwrap();
+        read_project(&mut buffer.as_slice()).unwrap().table
+    }
+
+    #[test]
+    fn round_trip_keeps_tasks() {
+        let mut table = Table::new_empty();
+        table.set_characters("#1");
+        table.set_states_number(2);
+        let tasks = [
+            (0, EMPTY_CHAR, task(1, '1', Direction::Right)),
+            (0, '1', task(1, '1', Direction::Left)),
+            (1, EMPTY_CHAR, task(0, '1', Direction::Left)),
+            (1, '1', Task::new()),
+        ];
+        for (state, read, task) in tasks {
+            table.set_task_by_state_and_character(task, state, read);
+        }
+        table.set_state_name("start".to_string(), 0);
+        table.set_state_kind(StateKind::Accepting, 1);
+
+        let read_table = write_and_read(table);
+
+        assert_eq!(read_table.get_states_number(), 2);
+        assert_eq!(read_table.get_state_name(0), "start");
+        assert_eq!(read_table.get_state_kind(1), StateKind::Accepting);
+        for (state, read, task) in tasks {
+            assert_eq!(read_table.get_task(state, read), Some(&task));
+        }
+    }
+
+    #[test]
+    fn stopping_task_goes_to_stop_state() {
+        let mut table = Table::new_empty();
+        table.set_characters("#1");
+        table.set_states_number(1);
+        table.set_task_by_state_and_character(task(0, '1', Direction::Stop), 0, EMPTY_CHAR);
+
+        let read_table = write_and_read(table);
+
+        assert_eq!(read_table.get_states_number(), 2);
+        assert_eq!(read_table.get_state_name(1), STOP_STATE_NAME);
+        assert_eq!(
+            read_table.get_task(0, EMPTY_CHAR),
+            Some(&task(1, '1', Direction::Right))
+        );
+        assert!(read_table.get_task(1, '1').unwrap().is_halting_for(1, '1'));
+    }
+
+    #[test]
+    fn stay_move_is_rejected() {
+        let xml = r#"<structure>
+            <type>turing</type>
+            <automaton>
+                <state id="0" name="q0"><initial/></state>
+                <transition><from>0</from><to>0</to><read/><write>1</write><move>S</move></transition>
+            </automaton>
+        </structure>"#;
+
+        let error = read_project(&mut xml.as_bytes()).err().unwrap();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        assert!(error.to_string().contains("S move"));
+    }
+}
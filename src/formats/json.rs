@@ -0,0 +1,319 @@
+//! Structured, versioned JSON project format.
+//!
+//! Unlike .mt files it can hold project metadata, test cases, names, comments
+//! and diagram positions of states and any number of states. Every table that
+//! can be saved as .mt can be converted to JSON and back without losing anything.
+
+use std::io::{prelude::*, Error, ErrorKind};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    constants::{MAX_STATES_NUMBER, MIN_STATES_NUMBER},
+    project::{Metadata, Project},
+    table::{StateKind, Table},
+    tape::{Tape, TapeMode},
+    task::{Direction, Task},
+    test_case::TestCase,
+};
+
+/// Version of the schema written by this program.
+/// It has to be increased after every change which older versions couldn't read
+pub const SCHEMA_VERSION: u64 = 1;
+
+const VERSION_KEY: &str = "version";
+
+#[derive(Serialize, Deserialize)]
+struct ProjectRecord {
+    version: u64,
+
+    #[serde(default)]
+    metadata: Metadata,
+
+    #[serde(default)]
+    tape: Option<TapeRecord>,
+
+    table: TableRecord,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tests: Vec<TestCase>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TapeRecord {
+    chars: String,
+    cursor_position: isize,
+    length: usize,
+    mode: TapeMode,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TableRecord {
+    /// Characters in the order in which they are shown in the editor
+    characters: String,
+    states: Vec<StateRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StateRecord {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    name: String,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    comment: String,
+
+    #[serde(default)]
+    kind: StateKind,
+
+    /// Position in the state diagram, missing if the state is placed automatically
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    position: Option<(f32, f32)>,
+
+    /// Tasks for characters missing here are the default ones
+    #[serde(default)]
+    tasks: Vec<TaskRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TaskRecord {
+    /// Character under the cursor for which the task is executed
+    read: char,
+    state: usize,
+    write: char,
+    direction: Direction,
+}
+
+pub fn read_project(buffer: &mut impl Read) -> Result<Project, Error> {
+    let invalid_data_error = || Error::from(ErrorKind::InvalidData);
+    let value: Value = serde_json::from_reader(buffer)?;
+
+    // The version is checked first, so newer files are rejected
+    // instead of being read incorrectly
+    match value.get(VERSION_KEY).and_then(Value::as_u64) {
+        Some(version) if version <= SCHEMA_VERSION => {}
+        Some(version) => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("unsupported schema version {}", version),
+            ))
+        }
+        None => return Err(invalid_data_error()),
+    }
+
+    let record: ProjectRecord = serde_json::from_value(value)?;
+    let states_number = record.table.states.len();
+    if !(MIN_STATES_NUMBER..=MAX_STATES_NUMBER).contains(&states_number) {
+        return Err(invalid_data_error());
+    }
+
+    let mut table = Table::new_empty();
+    table.set_characters(&record.table.characters);
+    table.set_states_number(states_number);
+
+    for (state, state_record) in record.table.states.into_iter().enumerate() {
+        table.set_state_kind(state_record.kind, state);
+        table.set_state_name(state_record.name, state);
+        table.set_state_comment(state_record.comment, state);
+        table.set_state_position(state_record.position, state);
+
+        for task_record in state_record.tasks {
+            let task = Task {
+                state: task_record.state,
+                character: task_record.write,
+                direction: task_record.direction,
+            };
+
+            table
+                .set_task_by_state_and_character(task, state, task_record.read)
+                .ok_or(invalid_data_error())?;
+        }
+    }
+
+    let mut tape = Tape::new();
+    if let Some(tape_record) = record.tape {
+        tape.set_mode(tape_record.mode);
+        tape.set_length(tape_record.length);
+        tape.set_chars(tape_record.chars);
+        tape.set_cursor_position(tape_record.cursor_position);
+    }
+
+    Ok(Project {
+        table,
+        tape,
+        metadata: record.metadata,
+        tests: record.tests,
+    })
+}
+
+pub fn write_project(project: &Project, buffer: &mut impl Write) -> Result<(), Error> {
+    let Project {
+        table,
+        tape,
+        metadata,
+        tests,
+    } = project;
+
+    let states = (0..table.get_states_number())
+        .map(|state| StateRecord {
+            name: table.get_state_name(state).to_string(),
+            comment: table.get_state_comment(state).to_string(),
+            kind: table.get_state_kind(state),
+            position: table.get_state_position(state),
+            tasks: table
+                .get_characters()
+                .chars()
+                .map(|read| {
+                    let task = table.get_task(state, read).unwrap();
+                    TaskRecord {
+                        read,
+                        state: task.state,
+                        write: task.character,
+                        direction: task.direction,
+                    }
+                })
+                .collect(),
+        })
+        .collect();
+
+    let record = ProjectRecord {
+        version: SCHEMA_VERSION,
+        metadata: metadata.clone(),
+        tape: Some(TapeRecord {
+            chars: tape.get_chars_without_margin().iter().collect(),
+            cursor_position: tape.get_cursor_position(),
+            length: tape.get_length(),
+            mode: tape.get_mode(),
+        }),
+        table: TableRecord {
+            characters: table.get_characters().clone(),
+            states,
+        },
+        tests: tests.clone(),
+    };
+
+    serde_json::to_writer_pretty(&mut *buffer, &record)?;
+    writeln!(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constants::EMPTY_CHAR, machine::HaltReason};
+
+    use super::*;
+
+    fn write(project: &Project) -> String {
+        let mut buffer = Vec::new();
+        write_project(project, &mut buffer).unwrap();
+        String::from_utf8(buffer).unwrap()
+    }
+
+    fn new_project(mode: TapeMode) -> Project {
+        let mut table = Table::new_empty();
+        table.set_characters("#ab");
+        table.set_states_number(3);
+        let tasks = [
+            (0, EMPTY_CHAR, Task::new_halting(0, EMPTY_CHAR)),
+            (
+                0,
+                'a',
+                Task {
+                    state: 1,
+                    character: 'b',
+                    direction: Direction::Right,
+                },
+            ),
+            (
+                1,
+                'b',
+                Task {
+                    state: 2,
+                    character: 'a',
+                    direction: Direction::Left,
+                },
+            ),
+        ];
+        for (state, read, task) in tasks {
+            table.set_task_by_state_and_character(task, state, read);
+        }
+        table.set_state_kind(StateKind::Accepting, 2);
+        table.set_state_name("start".to_string(), 0);
+        table.set_state_comment("reads the input".to_string(), 1);
+        table.set_state_position(Some((10.5, -20.0)), 2);
+
+        let mut tape = Tape::new();
+        tape.set_mode(mode);
+        tape.set_length(7);
+        tape.set_chars("ab".to_string());
+        tape.set_cursor_position(1);
+
+        Project {
+            table,
+            tape,
+            metadata: Metadata {
+                name: "Swap".to_string(),
+                author: "Author".to_string(),
+                description: "Swaps characters".to_string(),
+            },
+            tests: vec![TestCase {
+                name: "swap".to_string(),
+                tape: "ab".to_string(),
+                cursor_position: 0,
+                expected_tape: Some("ba".to_string()),
+                expected_halt_reason: Some(HaltReason::Accepted),
+                max_steps: 10,
+            }],
+        }
+    }
+
+    #[test]
+    fn round_trip_keeps_everything() {
+        let project = new_project(TapeMode::Bounded);
+        let text = write(&project);
+        let read = read_project(&mut text.as_bytes()).unwrap();
+
+        assert_eq!(write(&read), text);
+
+        let table = &read.table;
+        assert_eq!(table.get_characters(), "#ab");
+        assert_eq!(table.get_states_number(), 3);
+        assert_eq!(table.get_state_name(0), "start");
+        assert_eq!(table.get_state_comment(1), "reads the input");
+        assert_eq!(table.get_state_position(2), Some((10.5, -20.0)));
+        assert_eq!(table.get_state_kind(2), StateKind::Accepting);
+        assert_eq!(
+            table.get_task(1, 'b'),
+            Some(&Task {
+                state: 2,
+                character: 'a',
+                direction: Direction::Left
+            })
+        );
+
+        assert_eq!(read.tape.get_mode(), TapeMode::Bounded);
+        assert_eq!(read.tape.get_length(), 7);
+        assert_eq!(read.tape.get_cursor_position(), 1);
+        assert_eq!(read.tests, project.tests);
+        assert_eq!(read.metadata.name, "Swap");
+        assert_eq!(read.metadata.author, "Author");
+        assert_eq!(read.metadata.description, "Swaps characters");
+    }
+
+    #[test]
+    fn round_trip_keeps_unbounded_tape() {
+        let text = write(&new_project(TapeMode::Unbounded));
+        let read = read_project(&mut text.as_bytes()).unwrap();
+
+        assert_eq!(write(&read), text);
+        assert_eq!(read.tape.get_mode(), TapeMode::Unbounded);
+        assert_eq!(read.tape.get_cursor_position(), 1);
+    }
+
+    #[test]
+    fn newer_version_is_rejected() {
+        let text = format!(r#"{{"version": {}, "table": {{}}}}"#, SCHEMA_VERSION + 1);
+        let error = read_project(&mut text.as_bytes()).err().unwrap();
+
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,8 @@
+//! File formats other than the legacy .mt table and the .mtp project,
+//! which are read and written by `Table` and `Project` themselves
+
+pub mod bbchallenge;
+pub mod dot;
+pub mod jflap;
+pub mod json;
+pub mod turingmachine_io;
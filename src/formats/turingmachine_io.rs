@@ -0,0 +1,402 @@
+//! YAML documents of the turingmachine.io visualizer.
+//!
+//! The start state becomes state 0 and the other states keep their order.
+//! The blank symbol is mapped to EMPTY_CHAR and the input becomes the tape text.
+//! Documents using EMPTY_CHAR as a symbol which isn't blank are rejected,
+//! because it would be merged with the blank symbol.
+//! Cells without a rule halt the machine without changing the tape.
+//!
+//! The visualizer has no accepting or rejecting states, so all states are read
+//! and written as normal ones.
+//!
+//! The visualizer can't stop in place, so tasks with Direction::Stop which change
+//! the tape or the state are written as moves to the right into an extra state
+//! without any rules.
+
+use std::io::{prelude::*, Error, ErrorKind};
+
+use serde_yaml::Value;
+
+use crate::{
+    constants::{EMPTY_CHAR, MAX_STATES_NUMBER},
+    project::Project,
+    table::{single_char, Table},
+    tape::{Tape, TapeMode},
+    task::{Direction, Task},
+};
+
+const INPUT_KEY: &str = "input";
+const BLANK_KEY: &str = "blank";
+const START_STATE_KEY: &str = "start state";
+const TABLE_KEY: &str = "table";
+const WRITE_KEY: &str = "write";
+const LEFT_KEY: &str = "L";
+const RIGHT_KEY: &str = "R";
+const STOP_STATE_NAME: &str = "stop";
+
+pub fn read_project(buffer: &mut impl Read) -> Result<Project, Error> {
+    let invalid_data_error = || Error::from(ErrorKind::InvalidData);
+    let document: Value =
+        serde_yaml::from_reader(buffer).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+    let blank = match document.get(BLANK_KEY) {
+        Some(value) => parse_symbol(value, EMPTY_CHAR).ok_or(invalid_data_error())?,
+        None => return Err(invalid_data_error()),
+    };
+    let start_state = document
+        .get(START_STATE_KEY)
+        .and_then(scalar_to_string)
+        .ok_or(invalid_data_error())?;
+    let rules = document
+        .get(TABLE_KEY)
+        .and_then(Value::as_mapping)
+        .ok_or(invalid_data_error())?;
+
+    // The machine always starts in state 0
+    let mut names: Vec<String> = rules
+        .keys()
+        .map(|key| scalar_to_string(key).ok_or(invalid_data_error()))
+        .collect::<Result<_, _>>()?;
+    let start_position = names
+        .iter()
+        .position(|name| *name == start_state)
+        .ok_or(invalid_data_error())?;
+    let start_name = names.remove(start_position);
+    names.insert(0, start_name);
+
+    if names.len() > MAX_STATES_NUMBER {
+        return Err(invalid_data_error());
+    }
+
+    let state_index = |name: &str| {
+        names
+            .iter()
+            .position(|n| n == name)
+            .ok_or(invalid_data_error())
+    };
+
+    let mut tasks = Vec::new();
+    for (name, state_rules) in rules {
+        let state = state_index(&scalar_to_string(name).ok_or(invalid_data_error())?)?;
+
+        // States without rules halt the machine
+        let state_rules = match state_rules {
+            Value::Null => continue,
+            Value::Mapping(state_rules) => state_rules,
+            _ => return Err(invalid_data_error()),
+        };
+
+        for (symbols, instruction) in state_rules {
+            let (write, direction, next_state) =
+                parse_instruction(instruction, blank).ok_or(invalid_data_error())?;
+            let next_state = match next_state {
+                Some(name) => state_index(&name)?,
+                None => state,
+            };
+
+            let symbols = match symbols {
+                Value::Sequence(symbols) => symbols.iter().collect(),
+                symbol => vec![symbol],
+            };
+
+            for symbol in symbols {
+                let read = parse_symbol(symbol, blank).ok_or(invalid_data_error())?;
+                let task = Task {
+                    state: next_state,
+                    character: write.unwrap_or(read),
+                    direction,
+                };
+
+                tasks.push((state, read, task));
+            }
+        }
+    }
+
+    let mut characters = EMPTY_CHAR.to_string();
+    for (_, read, task) in &tasks {
+        for c in [*read, task.character] {
+            if !characters.contains(c) {
+                characters.push(c);
+            }
+        }
+    }
+
+    let mut table = Table::new_empty();
+    table.set_characters(&characters);
+    table.set_states_number(names.len());
+
+    // Cells without a rule are left default
+    for (state, name) in names.iter().enumerate() {
+        table.set_state_name(name.clone(), state);
+    }
+
+    for (state, read, task) in tasks {
+        table.set_task_by_state_and_character(task, state, read);
+    }
+
+    let input: String = match document.get(INPUT_KEY) {
+        Some(input) => scalar_to_string(input).ok_or(invalid_data_error())?,
+        None => String::new(),
+    };
+    let input: String = input
+        .chars()
+        .map(|c| map_blank(c, blank))
+        .collect::<Option<_>>()
+        .ok_or(invalid_data_error())?;
+
+    // Machines of turingmachine.io run on an infinite tape
+    let mut tape = Tape::new();
+    tape.set_mode(TapeMode::Unbounded);
+    tape.set_chars(input);
+
+    Ok(Project {
+        table,
+        tape,
+        ..Project::new_empty()
+    })
+}
+
+pub fn write_project(project: &Project, buffer: &mut impl Write) -> Result<(), Error> {
+    let Project { table, tape, .. } = project;
+    let mut transitions = Vec::new();
+
+    for state in 0..table.get_states_number() {
+        for character in table.get_characters().chars() {
+            let task = table.get_task(state, character).unwrap();
+            if !task.is_halting_for(state, character) {
+                transitions.push((state, character, *task));
+            }
+        }
+    }
+
+    // The visualizer requires every transition to go to an existing state,
+    // so states missing in the table are added without any rules
+    let states_number = transitions
+        .iter()
+        .map(|(_, _, task)| task.state + 1)
+        .fold(table.get_states_number(), usize::max);
+
+    // Names of states are keys of a mapping, so they have to be unique
+    let mut names: Vec<String> = Vec::with_capacity(states_number);
+    for state in 0..states_number {
+        let name = match table.get_state_name(state) {
+            "" => state.to_string(),
+            name => name.to_string(),
+        };
+        names.push(unique_name(name, &names));
+    }
+
+    // The extra state for stopping tasks needs a name which isn't used yet
+    let stop_state_name = unique_name(STOP_STATE_NAME.to_string(), &names);
+    let mut is_stop_state_used = false;
+
+    let input: String = tape.get_chars_without_margin().iter().collect();
+    writeln!(buffer, "{}: {}", INPUT_KEY, quote(&input))?;
+    writeln!(buffer, "{}: {}", BLANK_KEY, quote(&EMPTY_CHAR.to_string()))?;
+    writeln!(buffer, "{}: {}", START_STATE_KEY, quote(&names[0]))?;
+    writeln!(buffer, "{}:", TABLE_KEY)?;
+
+    for (state, name) in names.iter().enumerate() {
+        writeln!(buffer, "  {}:", quote(name))?;
+
+        for (_, character, task) in transitions.iter().filter(|(s, _, _)| *s == state) {
+            let (direction_key, next_state_name) = match task.direction {
+                Direction::Left => (LEFT_KEY, &names[task.state]),
+                Direction::Right => (RIGHT_KEY, &names[task.state]),
+                Direction::Stop => {
+                    is_stop_state_used = true;
+                    (RIGHT_KEY, &stop_state_name)
+                }
+            };
+
+            writeln!(
+                buffer,
+                "    {}: {{{}: {}, {}: {}}}",
+                quote(&character.to_string()),
+                WRITE_KEY,
+                quote(&task.character.to_string()),
+                direction_key,
+                quote(next_state_name)
+            )?;
+        }
+    }
+
+    if is_stop_state_used {
+        writeln!(buffer, "  {}:", quote(&stop_state_name))?;
+    }
+
+    Ok(())
+}
+
+/// Appends underscores to `name` until it differs from all `names`
+fn unique_name(mut name: String, names: &[String]) -> String {
+    while names.contains(&name) {
+        name.push('_');
+    }
+    name
+}
+
+/// Parses `L`, `R` or a mapping with an optional symbol to write and a move
+/// with an optional next state
+fn parse_instruction(
+    instruction: &Value,
+    blank: char,
+) -> Option<(Option<char>, Direction, Option<String>)> {
+    let parse_direction = |key: &str| match key {
+        LEFT_KEY => Some(Direction::Left),
+        RIGHT_KEY => Some(Direction::Right),
+        _ => None,
+    };
+
+    if let Value::String(key) = instruction {
+        return Some((None, parse_direction(key)?, None));
+    }
+
+    let mut write = None;
+    let mut movement = None;
+    for (key, value) in instruction.as_mapping()? {
+        match key.as_str()? {
+            WRITE_KEY => write = Some(parse_symbol(value, blank)?),
+            key => {
+                let next_state = match value {
+                    Value::Null => None,
+                    value => Some(scalar_to_string(value)?),
+                };
+                movement = Some((parse_direction(key)?, next_state));
+            }
+        }
+    }
+
+    let (direction, next_state) = movement?;
+    Some((write, direction, next_state))
+}
+
+fn parse_symbol(value: &Value, blank: char) -> Option<char> {
+    map_blank(single_char(&scalar_to_string(value)?)?, blank)
+}
+
+/// Maps the blank symbol to EMPTY_CHAR. Returns None for EMPTY_CHAR which isn't blank
+fn map_blank(c: char, blank: char) -> Option<char> {
+    match c {
+        c if c == blank => Some(EMPTY_CHAR),
+        EMPTY_CHAR => None,
+        c => Some(c),
+    }
+}
+
+/// Symbols and names of states can be written in YAML as strings, numbers or booleans
+fn scalar_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(state: usize, character: char, direction: Direction) -> Task {
+        Task {
+            state,
+            character,
+            direction,
+        }
+    }
+
+    fn write_and_read(project: &Project) -> Project {
+        let mut buffer = Vec::new();
+        write_project(project, &mut buffer).unwrap();
+        read_project(&mut buffer.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn round_trip_keeps_tasks_and_input() {
+        let mut table = Table::new_empty();
+        table.set_characters("#1");
+        table.set_states_number(2);
+        let tasks = [
+            (0, EMPTY_CHAR, task(1, '1', Direction::Right)),
+            (0, '1', task(0, '1', Direction::Left)),
+            (1, EMPTY_CHAR, task(0, '1', Direction::Left)),
+            (1, '1', Task::new()),
+        ];
+        for (state, read, task) in tasks {
+            table.set_task_by_state_and_character(task, state, read);
+        }
+        table.set_state_name("start".to_string(), 0);
+
+        let mut tape = Tape::new();
+        tape.set_mode(TapeMode::Unbounded);
+        tape.set_chars("1#1".to_string());
+
+        let project = write_and_read(&Project {
+            table,
+            tape,
+            ..Project::new_empty()
+        });
+
+        assert_eq!(project.table.get_states_number(), 2);
+        assert_eq!(project.table.get_state_name(0), "start");
+        for (state, read, task) in tasks {
+            assert_eq!(project.table.get_task(state, read), Some(&task));
+        }
+        assert_eq!(project.tape.get_chars_without_margin(), ['1', '#', '1']);
+    }
+
+    #[test]
+    fn stopping_task_goes_to_stop_state() {
+        let mut table = Table::new_empty();
+        table.set_characters("#1");
+        table.set_states_number(1);
+        table.set_task_by_state_and_character(task(0, '1', Direction::Stop), 0, EMPTY_CHAR);
+
+        let project = write_and_read(&Project {
+            table,
+            ..Project::new_empty()
+        });
+
+        assert_eq!(project.table.get_states_number(), 2);
+        assert_eq!(project.table.get_state_name(1), STOP_STATE_NAME);
+        assert_eq!(
+            project.table.get_task(0, EMPTY_CHAR),
+            Some(&task(1, '1', Direction::Right))
+        );
+    }
+
+    #[test]
+    fn other_blank_is_read_as_empty_char() {
+        let text =
+            "input: '1 1'\nblank: ' '\nstart state: a\ntable:\n  a:\n    ' ': {write: '1', R: a}\n";
+        let project = read_project(&mut text.as_bytes()).unwrap();
+
+        assert_eq!(
+            project.tape.get_chars_without_margin(),
+            ['1', EMPTY_CHAR, '1']
+        );
+        assert_eq!(
+            project.table.get_task(0, EMPTY_CHAR),
+            Some(&task(0, '1', Direction::Right))
+        );
+    }
+
+    #[test]
+    fn empty_char_which_isnt_blank_is_rejected() {
+        let texts = [
+            "blank: ' '\nstart state: a\ntable:\n  a:\n    '#': R\n",
+            "blank: ' '\nstart state: a\ntable:\n  a:\n    '1': {write: '#', R: a}\n",
+            "input: '1#1'\nblank: ' '\nstart state: a\ntable:\n  a:\n",
+        ];
+
+        for text in texts {
+            let error = read_project(&mut text.as_bytes()).err().unwrap();
+            assert_eq!(error.kind(), ErrorKind::InvalidData, "{}", text);
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use std::{
+    fs,
+    io::{prelude::*, Error},
+    path::Path,
+};
+
+use serde::Serialize;
+
+use crate::{
+    constants::FILE_EXTENSION, machine::HaltReason, project::Project, test_case::TestCase,
+};
+
+const CSV_HEADER: [&str; 7] = [
+    "file",
+    "test",
+    "passed",
+    "steps",
+    "tape",
+    "halt_reason",
+    "error",
+];
+
+/// Results of running the same test cases on many tables
+#[derive(Debug, Clone, Serialize)]
+pub struct Gradebook {
+    /// Number of test cases run on every table
+    pub tests_number: usize,
+    pub submissions: Vec<Submission>,
+}
+
+/// Results of a single table
+#[derive(Debug, Clone, Serialize)]
+pub struct Submission {
+    /// Name of the file with the table
+    pub file: String,
+
+    /// Error of opening the file, in which case no tests are run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    pub passed_tests: usize,
+    pub tests: Vec<GradedTest>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GradedTest {
+    pub name: String,
+    pub passed: bool,
+    pub steps: usize,
+
+    /// Final tape without blank characters on its ends
+    pub tape: String,
+
+    /// None if the step limit was reached
+    pub halt_reason: Option<HaltReason>,
+
+    /// Descriptions of the reasons why the test has failed
+    pub failures: Vec<String>,
+}
+
+impl Gradebook {
+    /// Runs `tests` on every .mt file in `directory`, in the order of file names.
+    /// Tests which have a step budget larger than `max_steps` are limited to it
+    pub fn new_from_directory(
+        directory: &Path,
+        tests: &[TestCase],
+        max_steps: Option<usize>,
+    ) -> Result<Self, Error> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(directory)? {
+            let path = entry?.path();
+            if path.is_file()
+                && path.extension().and_then(|ext| ext.to_str()) == Some(FILE_EXTENSION)
+            {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let tests: Vec<TestCase> = tests
+            .iter()
+            .map(|test| TestCase {
+                max_steps: max_steps.map_or(test.max_steps, |max| test.max_steps.min(max)),
+                ..test.clone()
+            })
+            .collect();
+
+        let submissions = paths
+            .iter()
+            .map(|path| {
+                let file = path.file_name().unwrap().to_string_lossy().to_string();
+                match Project::new_from_file(path) {
+                    Ok(project) => Submission::new(file, &project, &tests),
+                    Err(e) => Submission {
+                        file,
+                        error: Some(e.to_string()),
+                        passed_tests: 0,
+                        tests: Vec::new(),
+                    },
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            tests_number: tests.len(),
+            submissions,
+        })
+    }
+
+    /// Writes one line for every test of every table and one line for every file
+    /// which couldn't be opened
+    pub fn write_csv(&self, buffer: &mut impl Write) -> Result<(), Error> {
+        writeln!(buffer, "{}", CSV_HEADER.join(","))?;
+
+        for submission in &self.submissions {
+            if let Some(error) = &submission.error {
+                let fields = [submission.file.as_str(), "", "", "", "", "", error];
+                writeln!(buffer, "{}", csv_line(&fields))?;
+                continue;
+            }
+
+            for test in &submission.tests {
+                let halt_reason = match test.halt_reason {
+                    Some(reason) => serde_json::to_value(reason)?
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                    None => String::new(),
+                };
+                let fields = [
+                    submission.file.as_str(),
+                    &test.name,
+                    &test.passed.to_string(),
+                    &test.steps.to_string(),
+                    &test.tape,
+                    &halt_reason,
+                    &test.failures.join("; "),
+                ];
+                writeln!(buffer, "{}", csv_line(&fields))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn write_json(&self, buffer: &mut impl Write) -> Result<(), Error> {
+        serde_json::to_writer_pretty(&mut *buffer, self)?;
+        writeln!(buffer)
+    }
+}
+
+impl Submission {
+    fn new(file: String, project: &Project, tests: &[TestCase]) -> Self {
+        let tests: Vec<GradedTest> = tests
+            .iter()
+            .map(|test| {
+                let result = test.run(&project.table, &project.tape);
+                GradedTest {
+                    name: test.name.clone(),
+                    passed: result.is_passed(),
+                    steps: result.steps,
+                    tape: result.tape,
+                    halt_reason: result.halt_reason,
+                    failures: result.failures.iter().map(|f| f.to_string()).collect(),
+                }
+            })
+            .collect();
+
+        Self {
+            file,
+            error: None,
+            passed_tests: tests.iter().filter(|test| test.passed).count(),
+            tests,
+        }
+    }
+}
+
+/// Joins the fields with commas, quoting the ones which contain commas, quotes or line breaks
+fn csv_line(fields: &[&str]) -> String {
+    fields
+        .iter()
+        .map(|field| match field.contains([',', '"', '\n', '\r']) {
+            true => format!("\"{}\"", field.replace('"', "\"\"")),
+            false => field.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, path::PathBuf};
+
+    use crate::{
+        constants::EMPTY_CHAR,
+        table::Table,
+        task::{Direction, Task},
+    };
+
+    use super::*;
+
+    /// Creates an empty directory for the files of a single test
+    fn new_directory(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!(
+            "turing-machine-gradebook-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    /// Writes a table which moves back and forth between two cells forever
+    fn write_bouncing_table(path: &Path) {
+        let mut table = Table::new_empty();
+        table.set_characters("#1");
+        table.set_states_number(2);
+        for (state, next_state, direction) in [(0, 1, Direction::Right), (1, 0, Direction::Left)] {
+            let task = Task {
+                state: next_state,
+                character: EMPTY_CHAR,
+                direction,
+            };
+            table.set_task_by_state_and_character(task, state, EMPTY_CHAR);
+        }
+
+        table
+            .write_to_buffer(&mut fs::File::create(path).unwrap())
+            .unwrap();
+    }
+
+    fn test_case(max_steps: usize) -> TestCase {
+        TestCase {
+            name: "test".to_string(),
+            tape: String::new(),
+            cursor_position: 0,
+            expected_tape: None,
+            expected_halt_reason: None,
+            max_steps,
+        }
+    }
+
+    #[test]
+    fn csv_fields_with_separators_are_quoted() {
+        let fields = ["plain", "a,b", "say \"hi\"", "two\nlines", "cr\r"];
+
+        assert_eq!(
+            csv_line(&fields),
+            "plain,\"a,b\",\"say \"\"hi\"\"\",\"two\nlines\",\"cr\r\""
+        );
+    }
+
+    #[test]
+    fn only_mt_files_are_graded() {
+        let directory = new_directory("only-mt");
+        write_bouncing_table(&directory.join("b.mt"));
+        write_bouncing_table(&directory.join("a.mt"));
+        fs::write(directory.join("notes.txt"), "not a table").unwrap();
+        fs::write(directory.join("project.json"), "{}").unwrap();
+        fs::create_dir(directory.join("folder.mt")).unwrap();
+
+        let gradebook = Gradebook::new_from_directory(&directory, &[test_case(10)], None).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        let files: Vec<&str> = gradebook
+            .submissions
+            .iter()
+            .map(|submission| submission.file.as_str())
+            .collect();
+        assert_eq!(files, ["a.mt", "b.mt"]);
+    }
+
+    #[test]
+    fn step_limit_is_reported_as_not_halted() {
+        let directory = new_directory("max-steps");
+        write_bouncing_table(&directory.join("student, \"one\".mt"));
+
+        let gradebook =
+            Gradebook::new_from_directory(&directory, &[test_case(1000)], Some(10)).unwrap();
+        fs::remove_dir_all(&directory).unwrap();
+
+        let test = &gradebook.submissions[0].tests[0];
+        assert!(!test.passed);
+        assert_eq!(test.steps, 10);
+        assert_eq!(test.halt_reason, None);
+        assert_eq!(gradebook.submissions[0].passed_tests, 0);
+
+        let mut csv = Vec::new();
+        gradebook.write_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let line = csv.lines().nth(1).unwrap();
+        assert!(line.starts_with("\"student, \"\"one\"\".mt\",test,false,10,,,"));
+    }
+}
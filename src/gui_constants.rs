@@ -0,0 +1,34 @@
+use iced::Font;
+use image::ImageFormat;
+
+pub const MAX_TAPE_FONT_SIZE: u16 = 30;
+pub const MIN_TAPE_FONT_SIZE: u16 = 14;
+pub const SCALE_FACTOR_STEP: f64 = 0.1;
+/// Pairs of the self-timer interval in milliseconds and the number of steps
+/// executed every interval, from the slowest to the fastest
+pub const MACHINE_SELF_TIMER_SPEEDS: [(u64, usize); 11] = [
+    (1000, 1),
+    (500, 1),
+    (200, 1),
+    (100, 1),
+    (50, 1),
+    (20, 1),
+    (10, 1),
+    (10, 10),
+    (10, 100),
+    (10, 1000),
+    (10, 10_000),
+];
+pub const DEFAULT_RUN_STEP_BUDGET: usize = 1_000_000;
+pub const RUN_PROGRESS_INTERVAL: u64 = 100;
+/// Forward jumps to a step longer than this are run in the background
+pub const MAX_UI_THREAD_JUMP: usize = 10_000;
+pub const TAPE_FONT_BYTES: &[u8] = include_bytes!("../RobotoMono/fonts/ttf/RobotoMono-Medium.ttf");
+pub const TAPE_FONT: Font = Font::External {
+    name: "Roboto Mono",
+    bytes: TAPE_FONT_BYTES,
+};
+pub const ICON_BYTES: &[u8] = include_bytes!("../icon.ico");
+pub const ICON_FORMAT: ImageFormat = ImageFormat::Ico;
+pub const PNG_FILE_EXTENSION: &str = "png";
+pub const GIF_FILE_EXTENSION: &str = "gif";
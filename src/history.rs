@@ -0,0 +1,201 @@
+//! Undo and redo of changes made in the editor.
+//!
+//! The history stores whole snapshots of the document instead of inverse edits,
+//! so every kind of change can be undone without code of its own. The cost is memory:
+//! the undo and redo stacks together hold at most MAX_HISTORY_LENGTH snapshots.
+//! Tasks take 16 bytes each, so a snapshot of a table with MAX_STATES_NUMBER states
+//! and 20 characters takes about 320 kB and the history of such a table up to 32 MB.
+//! Tables with a few states need only a few kilobytes per snapshot.
+
+use crate::constants::MAX_HISTORY_LENGTH;
+
+/// Kind of change made in the editor together with the cell or state it changes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edit {
+    TapeChars,
+    TapeCursorPosition,
+    TapeLength,
+    TapeMode,
+    TableCharacters,
+    TableStatesNumber,
+    StateKind(usize),
+    StatePosition(usize),
+    StatePositionsCleared,
+
+    /// Row and column of the changed task
+    Task(usize, usize),
+
+    TestAdded,
+
+    /// Index of the changed test
+    TestRemoved(usize),
+    TestName(usize),
+    TestExpectedTape(usize),
+    TestExpectedHaltReason(usize),
+    TestMaxSteps(usize),
+}
+
+/// Undo and redo stacks of snapshots of the edited document
+pub struct EditHistory<T> {
+    undo_stack: Vec<T>,
+    redo_stack: Vec<T>,
+
+    /// Edit which can be merged with the next one, e.g. while typing or dragging a state
+    last_edit: Option<Edit>,
+}
+
+impl<T> EditHistory<T> {
+    pub fn new() -> Self {
+        Self {
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            last_edit: None,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.last_edit = None;
+    }
+
+    /// Saves the document from before `edit`. Repeated edits of the same kind
+    /// are undone together until `finish_edit` is called
+    pub fn record(&mut self, edit: Edit, before: T) {
+        self.redo_stack.clear();
+        if self.last_edit == Some(edit) {
+            return;
+        }
+
+        if self.undo_stack.len() == MAX_HISTORY_LENGTH {
+            self.undo_stack.remove(0);
+        }
+        self.undo_stack.push(before);
+        self.last_edit = Some(edit);
+    }
+
+    /// Makes the next edit a separate step even if it is of the same kind as the last one
+    pub fn finish_edit(&mut self) {
+        self.last_edit = None;
+    }
+
+    /// Returns the document from before the last edit, or None if there is nothing to undo
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo_stack.pop()?;
+        self.redo_stack.push(current);
+        self.last_edit = None;
+        Some(previous)
+    }
+
+    /// Returns the document from before the last undo, or None if there is nothing to redo
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo_stack.pop()?;
+        self.undo_stack.push(current);
+        self.last_edit = None;
+        Some(next)
+    }
+}
+
+impl<T> Default for EditHistory<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Records `edit` of the document from `current` to `next` like the editor does
+    fn edit(history: &mut EditHistory<u32>, current: &mut u32, edit: Edit, next: u32) {
+        history.record(edit, *current);
+        *current = next;
+    }
+
+    #[test]
+    fn edits_are_undone_and_redone_in_order() {
+        let mut history = EditHistory::new();
+        let mut document = 0;
+        edit(&mut history, &mut document, Edit::TapeChars, 1);
+        edit(&mut history, &mut document, Edit::TapeLength, 2);
+
+        assert_eq!(history.undo(document), Some(1));
+        assert_eq!(history.undo(1), Some(0));
+        assert_eq!(history.undo(0), None);
+
+        assert_eq!(history.redo(0), Some(1));
+        assert_eq!(history.redo(1), Some(2));
+        assert_eq!(history.redo(2), None);
+    }
+
+    #[test]
+    fn repeated_edits_are_merged_until_finished() {
+        let mut history = EditHistory::new();
+        let mut document = 0;
+        edit(&mut history, &mut document, Edit::Task(1, 2), 1);
+        edit(&mut history, &mut document, Edit::Task(1, 2), 2);
+        history.finish_edit();
+        edit(&mut history, &mut document, Edit::Task(1, 2), 3);
+        edit(&mut history, &mut document, Edit::Task(2, 2), 4);
+
+        assert_eq!(history.undo(document), Some(3));
+        assert_eq!(history.undo(3), Some(2));
+        assert_eq!(history.undo(2), Some(0));
+        assert_eq!(history.undo(0), None);
+    }
+
+    #[test]
+    fn edit_after_undo_is_not_merged() {
+        let mut history = EditHistory::new();
+        let mut document = 0;
+        edit(&mut history, &mut document, Edit::TapeChars, 1);
+        edit(&mut history, &mut document, Edit::TapeChars, 2);
+        document = history.undo(document).unwrap();
+        edit(&mut history, &mut document, Edit::TapeChars, 3);
+
+        assert_eq!(history.undo(document), Some(0));
+    }
+
+    #[test]
+    fn new_edit_clears_redo() {
+        let mut history = EditHistory::new();
+        let mut document = 0;
+        edit(&mut history, &mut document, Edit::TapeChars, 1);
+        document = history.undo(document).unwrap();
+        edit(&mut history, &mut document, Edit::TestAdded, 2);
+
+        assert_eq!(history.redo(document), None);
+        assert_eq!(history.undo(document), Some(0));
+    }
+
+    #[test]
+    fn only_last_snapshots_are_kept() {
+        let mut history = EditHistory::new();
+        let mut document = 0;
+        for next in 1..=MAX_HISTORY_LENGTH as u32 + 10 {
+            edit(&mut history, &mut document, Edit::TestName(0), next);
+            history.finish_edit();
+        }
+
+        let mut undone_edits = 0;
+        while let Some(previous) = history.undo(document) {
+            document = previous;
+            undone_edits += 1;
+        }
+        assert_eq!(undone_edits, MAX_HISTORY_LENGTH);
+        assert_eq!(document, 10);
+    }
+
+    #[test]
+    fn clear_forgets_everything() {
+        let mut history = EditHistory::new();
+        let mut document = 0;
+        edit(&mut history, &mut document, Edit::TapeChars, 1);
+        edit(&mut history, &mut document, Edit::TapeMode, 2);
+        document = history.undo(document).unwrap();
+        history.clear();
+
+        assert_eq!(history.undo(document), None);
+        assert_eq!(history.redo(document), None);
+    }
+}
@@ -1,24 +1,53 @@
 use crate::{
-    constants::FILE_EXTENSION,
-    dialogs::{AboutProgramDialogLabels, DialogTexsts},
+    dialogs::{AboutProgramDialogLabels, DialogTexsts, ParseErrorTexts},
     my_theme::ThemeNames,
-    scene::{EditorSceneTexts, SideColumnTexts, SimulationSceneTexts},
+    scene::{
+        EditorSceneTexts, SideColumnTexts, SimulationSceneTexts, TasksTableTexts, TestsSceneTexts,
+    },
 };
 
+use turing_machine::constants::JSON_FILE_EXTENSION;
+
 use super::Language;
 
 pub const ENGLISH_LANGUAGE: &Language = &Language {
     language_name: "English",
     app_name: "Turing Machine",
-    default_filename: const_str::concat!("new.", FILE_EXTENSION),
+    default_filename: const_str::concat!("new.", JSON_FILE_EXTENSION),
     dialog_texts: DialogTexsts {
         file_filter_name: "Turing Machine file",
+        project_file_filter_name: "Turing Machine project",
+        table_file_filter_name: "Turing Machine table",
+        json_file_filter_name: "Turing Machine JSON project",
+        jflap_file_filter_name: "JFLAP Turing machine",
+        yaml_file_filter_name: "turingmachine.io machine",
+        bbchallenge_file_filter_name: "Busy beaver (bbchallenge notation)",
+        dot_file_filter_name: "Graphviz state diagram",
+        png_file_filter_name: "PNG image",
+        gif_file_filter_name: "GIF animation",
+        parse_error_texts: ParseErrorTexts {
+            line_label: "Line",
+            column_label: "column",
+            expected_label: "expected",
+            found_label: "found",
+            end_of_line_text: "end of line",
+            state_number_text: "state number",
+            character_text: "character",
+            direction_text: "direction (+, - or 0)",
+            state_kind_marker_text: "accept, reject or end of line",
+        },
         open_file_error_description: "Wrong file format",
         save_file_error_description: "Failed to save the file",
+        export_file_error_description: "Failed to export the file",
+        add_test_error_description:
+            "The machine doesn't halt within the step budget of a test, so there is no result to expect",
         error_message_title: "Error",
         unsaved_file_dialog_title: "Unsaved changes",
         unsaved_file_dialog_description:
             "This file contains unsaved changes. Do you want to save this file?",
+        lossy_file_dialog_title: "Some data won't be saved",
+        lossy_file_dialog_description:
+            "This format doesn't keep everything in the project, e.g. tests, the name, author and description of the project, names, comments and diagram positions of states or the tape. Only JSON projects keep all of them. Do you want to save this file without them?",
     },
     editor_scene_texts: EditorSceneTexts {
         initial_tape_input_placeholder: "Set initial tape...",
@@ -27,30 +56,89 @@ pub const ENGLISH_LANGUAGE: &Language = &Language {
         initial_cursor_position_input_label: "Cursor position",
         tape_length_input_placeholder: "Set tape length...",
         tape_length_input_label: "Tape length",
+        unbounded_tape_checkbox_label: "Grow tape on demand",
         table_characters_input_placeholder: "Set table characters...",
         table_characters_input_label: "Table characters",
         table_states_number_input_placeholder: "Set table states number...",
         table_states_number_input_label: "Table states number",
         start_machine_button_text: "Start",
+        arrange_state_diagram_button_text: "Arrange diagram",
+        tests_button_text: "Tests",
     },
     side_column_texts: SideColumnTexts {
         new_file_button_text: "New file",
         open_file_button_text: "Open file",
         save_file_button_text: "Save file",
         save_file_as_button_text: "Save file as",
+        export_state_diagram_button_text: "Export state diagram",
+        export_space_time_diagram_button_text: "Export space-time diagram",
+        export_animation_button_text: "Export animation",
         help_button_text: "Help",
         help_url: "https://github.com/Gojodzojo/turing-machine/blob/main/readmes/README_EN.md",
         about_program_button_text: "About",
         theme_picker_label: "Theme",
     },
     simulation_scene_texts: SimulationSceneTexts {
-        machine_halted_text: "Machine halted",
-        self_timer_interval_none_text: "None",
-        self_timer_interval_input_label: "Self-timer interval",
+        no_rule_halt_text: "Machine halted: no rule for this state and character",
+        stop_halt_text: "Machine halted: stop direction",
+        out_of_tape_halt_text: "Machine halted: cursor went beyond the tape",
+        accepted_halt_text: "Machine halted: accepted",
+        rejected_halt_text: "Machine halted: rejected",
+        loops_forever_halt_text: "Machine halted: it loops forever",
+        detect_loops_checkbox_label: "Detect infinite loops",
+        self_timer_speed_none_text: "None",
+        self_timer_speed_input_label: "Self-timer speed",
+        steps_per_second_text: "steps/s",
         stop_machine_button_text: "Stop",
         next_step_button_text: "Next step",
+        previous_step_button_text: "Previous step",
+        go_to_step_input_placeholder: "Set step...",
+        go_to_step_input_label: "Go to step",
         step_text: "Step",
         state_text: "State",
+        run_to_end_button_text: "Run to end",
+        run_step_budget_input_label: "Step budget",
+        run_step_budget_input_placeholder: "Set step budget...",
+        running_text: "Running",
+        cancel_run_button_text: "Cancel",
+    },
+    tasks_table_texts: TasksTableTexts {
+        normal_state_label: "-",
+        accepting_state_label: "Accept",
+        rejecting_state_label: "Reject",
+        state_out_of_range_warning: "Goes to a state which doesn't exist",
+        unknown_character_warning: "Writes a character which isn't one of the table characters",
+        unreachable_state_warning: "This state can't be reached from state 0",
+        default_row_warning: "All tasks of this state are default",
+        default_column_warning: "All tasks for this character are default",
+    },
+    tests_scene_texts: TestsSceneTexts {
+        run_tests_button_text: "Run tests",
+        add_test_button_text: "Add test",
+        add_test_description: "Adds a test which expects the current result of the initial tape",
+        close_tests_button_text: "Back to editor",
+        remove_test_button_text: "Remove",
+        test_name_input_placeholder: "Enter test name...",
+        default_test_name: "Test",
+        passed_tests_text: "Passed tests",
+        passed_text: "Passed",
+        failed_text: "Failed",
+        running_text: "Running...",
+        input_text: "Input",
+        cursor_position_text: "Cursor position",
+        tape_text: "Tape",
+        halt_reason_text: "Halt reason",
+        expected_text: "Expected",
+        steps_text: "Steps",
+        max_steps_text: "Step budget",
+        not_checked_text: "Not checked",
+        step_limit_reached_text: "step limit reached",
+        no_rule_halt_text: "no rule for the state and character",
+        stop_halt_text: "stop direction",
+        out_of_tape_halt_text: "cursor went beyond the tape",
+        accepted_halt_text: "accepted",
+        rejected_halt_text: "rejected",
+        loops_forever_halt_text: "loops forever",
     },
     theme_names: ThemeNames {
         dark: "Dark",
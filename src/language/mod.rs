@@ -3,7 +3,9 @@ use std::str::FromStr;
 use crate::{
     dialogs::{AboutProgramDialogLabels, DialogTexsts},
     my_theme::ThemeNames,
-    scene::{EditorSceneTexts, SideColumnTexts, SimulationSceneTexts},
+    scene::{
+        EditorSceneTexts, SideColumnTexts, SimulationSceneTexts, TasksTableTexts, TestsSceneTexts,
+    },
 };
 
 pub mod english;
@@ -52,6 +54,8 @@ pub struct Language {
     pub editor_scene_texts: EditorSceneTexts,
     pub side_column_texts: SideColumnTexts,
     pub simulation_scene_texts: SimulationSceneTexts,
+    pub tasks_table_texts: TasksTableTexts,
+    pub tests_scene_texts: TestsSceneTexts,
     pub theme_names: ThemeNames,
     pub about_program_dialog_labels: AboutProgramDialogLabels,
 }
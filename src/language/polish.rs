@@ -1,24 +1,53 @@
 use crate::{
-    constants::FILE_EXTENSION,
-    dialogs::{AboutProgramDialogLabels, DialogTexsts},
+    dialogs::{AboutProgramDialogLabels, DialogTexsts, ParseErrorTexts},
     my_theme::ThemeNames,
-    scene::{EditorSceneTexts, SideColumnTexts, SimulationSceneTexts},
+    scene::{
+        EditorSceneTexts, SideColumnTexts, SimulationSceneTexts, TasksTableTexts, TestsSceneTexts,
+    },
 };
 
+use turing_machine::constants::JSON_FILE_EXTENSION;
+
 use super::Language;
 
 pub const POLISH_LANGUAGE: &Language = &Language {
     language_name: "Polski",
     app_name: "Maszyna Turinga",
-    default_filename: const_str::concat!("nowy.", FILE_EXTENSION),
+    default_filename: const_str::concat!("nowy.", JSON_FILE_EXTENSION),
     dialog_texts: DialogTexsts {
         file_filter_name: "Plik Maszyny Turinga",
+        project_file_filter_name: "Projekt Maszyny Turinga",
+        table_file_filter_name: "Tablica Maszyny Turinga",
+        json_file_filter_name: "Projekt Maszyny Turinga JSON",
+        jflap_file_filter_name: "Maszyna Turinga JFLAP",
+        yaml_file_filter_name: "Maszyna turingmachine.io",
+        bbchallenge_file_filter_name: "Pracowity bóbr (notacja bbchallenge)",
+        dot_file_filter_name: "Diagram stanów Graphviz",
+        png_file_filter_name: "Obraz PNG",
+        gif_file_filter_name: "Animacja GIF",
+        parse_error_texts: ParseErrorTexts {
+            line_label: "Wiersz",
+            column_label: "kolumna",
+            expected_label: "oczekiwano",
+            found_label: "znaleziono",
+            end_of_line_text: "koniec wiersza",
+            state_number_text: "numeru stanu",
+            character_text: "znaku",
+            direction_text: "kierunku (+, - lub 0)",
+            state_kind_marker_text: "accept, reject lub końca wiersza",
+        },
         open_file_error_description: "Zły format pliku",
         save_file_error_description: "Nie udało się zapisać pliku",
+        export_file_error_description: "Nie udało się wyeksportować pliku",
+        add_test_error_description:
+            "Maszyna nie zatrzymuje się w limicie kroków testu, więc nie ma wyniku, którego można oczekiwać",
         error_message_title: "Błąd",
         unsaved_file_dialog_title: "Niezapisane zmiany",
         unsaved_file_dialog_description:
             "Ten plik zawiera niezapisane zmiany. Czy chcesz je zapisać?",
+        lossy_file_dialog_title: "Część danych nie zostanie zapisana",
+        lossy_file_dialog_description:
+            "Ten format nie przechowuje wszystkiego z projektu, np. testów, nazwy, autora i opisu projektu, nazw, komentarzy i pozycji stanów na diagramie lub taśmy. Tylko projekty JSON przechowują je wszystkie. Czy chcesz zapisać ten plik bez nich?",
     },
     editor_scene_texts: EditorSceneTexts {
         initial_tape_input_placeholder: "Wpisz tekst taśmy...",
@@ -27,30 +56,89 @@ pub const POLISH_LANGUAGE: &Language = &Language {
         initial_cursor_position_input_label: "Pozycja kursora",
         tape_length_input_placeholder: "Ustaw długość taśmy...",
         tape_length_input_label: "Długość taśmy",
+        unbounded_tape_checkbox_label: "Powiększaj taśmę w razie potrzeby",
         table_characters_input_placeholder: "Wpisz znaki tablicy...",
         table_characters_input_label: "Znaki tablicy",
         table_states_number_input_placeholder: "Ustaw liczbę stanów tablicy...",
         table_states_number_input_label: "Liczba stanów tablicy",
         start_machine_button_text: "Start",
+        arrange_state_diagram_button_text: "Uporządkuj diagram",
+        tests_button_text: "Testy",
     },
     side_column_texts: SideColumnTexts {
         new_file_button_text: "Nowy plik",
         open_file_button_text: "Otwórz plik",
         save_file_button_text: "Zapisz plik",
         save_file_as_button_text: "Zapisz plik jako",
+        export_state_diagram_button_text: "Eksportuj diagram stanów",
+        export_space_time_diagram_button_text: "Eksportuj diagram czasoprzestrzenny",
+        export_animation_button_text: "Eksportuj animację",
         help_button_text: "Pomoc",
         help_url: "https://github.com/Gojodzojo/turing-machine/blob/main/readmes/README_PL.md",
         about_program_button_text: "O programie",
         theme_picker_label: "Motyw",
     },
     simulation_scene_texts: SimulationSceneTexts {
-        machine_halted_text: "Maszyna zatrzymana",
-        self_timer_interval_none_text: "Brak",
-        self_timer_interval_input_label: "Interwał samowyzwalacza",
+        no_rule_halt_text: "Maszyna zatrzymana: brak reguły dla tego stanu i znaku",
+        stop_halt_text: "Maszyna zatrzymana: kierunek stop",
+        out_of_tape_halt_text: "Maszyna zatrzymana: kursor wyjechał poza taśmę",
+        accepted_halt_text: "Maszyna zatrzymana: zaakceptowano",
+        rejected_halt_text: "Maszyna zatrzymana: odrzucono",
+        loops_forever_halt_text: "Maszyna zatrzymana: zapętla się w nieskończoność",
+        detect_loops_checkbox_label: "Wykrywaj nieskończone pętle",
+        self_timer_speed_none_text: "Brak",
+        self_timer_speed_input_label: "Prędkość samowyzwalacza",
+        steps_per_second_text: "kroków/s",
         stop_machine_button_text: "Stop",
         next_step_button_text: "Następny krok",
+        previous_step_button_text: "Poprzedni krok",
+        go_to_step_input_placeholder: "Ustaw krok...",
+        go_to_step_input_label: "Przejdź do kroku",
         step_text: "Krok",
         state_text: "Stan",
+        run_to_end_button_text: "Uruchom do końca",
+        run_step_budget_input_label: "Limit kroków",
+        run_step_budget_input_placeholder: "Ustaw limit kroków...",
+        running_text: "Wykonywanie",
+        cancel_run_button_text: "Anuluj",
+    },
+    tasks_table_texts: TasksTableTexts {
+        normal_state_label: "-",
+        accepting_state_label: "Akceptuj",
+        rejecting_state_label: "Odrzuć",
+        state_out_of_range_warning: "Przechodzi do stanu, który nie istnieje",
+        unknown_character_warning: "Wpisuje znak, którego nie ma wśród znaków tablicy",
+        unreachable_state_warning: "Do tego stanu nie da się dojść ze stanu 0",
+        default_row_warning: "Wszystkie komórki tego stanu są domyślne",
+        default_column_warning: "Wszystkie komórki tego znaku są domyślne",
+    },
+    tests_scene_texts: TestsSceneTexts {
+        run_tests_button_text: "Uruchom testy",
+        add_test_button_text: "Dodaj test",
+        add_test_description: "Dodaje test, który oczekuje obecnego wyniku dla taśmy początkowej",
+        close_tests_button_text: "Wróć do edytora",
+        remove_test_button_text: "Usuń",
+        test_name_input_placeholder: "Wpisz nazwę testu...",
+        default_test_name: "Test",
+        passed_tests_text: "Zaliczone testy",
+        passed_text: "Zaliczony",
+        failed_text: "Niezaliczony",
+        running_text: "Uruchamianie...",
+        input_text: "Wejście",
+        cursor_position_text: "Pozycja kursora",
+        tape_text: "Taśma",
+        halt_reason_text: "Powód zatrzymania",
+        expected_text: "Oczekiwano",
+        steps_text: "Kroki",
+        max_steps_text: "Limit kroków",
+        not_checked_text: "Nie sprawdzane",
+        step_limit_reached_text: "osiągnięto limit kroków",
+        no_rule_halt_text: "brak reguły dla stanu i znaku",
+        stop_halt_text: "kierunek stop",
+        out_of_tape_halt_text: "kursor wyszedł poza taśmę",
+        accepted_halt_text: "zaakceptowano",
+        rejected_halt_text: "odrzucono",
+        loops_forever_halt_text: "zapętla się w nieskończoność",
     },
     theme_names: ThemeNames {
         dark: "Ciemny",
@@ -0,0 +1,32 @@
+//! Core of the Turing machine simulator.
+//!
+//! This crate contains everything needed to load, edit and run a table of rules
+//! without any GUI dependencies. The desktop application is built on top of it
+//! and is enabled with the `gui` feature.
+
+pub mod breakpoints;
+pub mod constants;
+pub mod cycle_detector;
+pub mod diagram;
+pub mod formats;
+pub mod gradebook;
+pub mod history;
+pub mod lint;
+pub mod machine;
+pub mod project;
+pub mod table;
+pub mod tape;
+pub mod task;
+pub mod test_case;
+
+pub use breakpoints::Breakpoints;
+pub use cycle_detector::CycleDetector;
+pub use gradebook::Gradebook;
+pub use history::{Edit, EditHistory};
+pub use lint::{lint, LintWarning};
+pub use machine::{HaltReason, Machine, StepDelta};
+pub use project::{Metadata, Project};
+pub use table::{ParseError, StateKind, Table};
+pub use tape::{OutOfTapeError, Tape, TapeMode};
+pub use task::{Direction, Task};
+pub use test_case::{TestCase, TestFailure, TestResult, TestSuite};
@@ -0,0 +1,292 @@
+use core::fmt;
+use std::collections::VecDeque;
+
+use crate::{
+    constants::DEFAULT_STATE,
+    table::{StateKind, Table},
+};
+
+/// Problem found in a table by `lint`.
+/// Default tasks and tasks which only halt the machine are treated as empty cells,
+/// so they are never reported themselves
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LintWarning {
+    /// The task of the cell goes to a state which doesn't exist
+    StateOutOfRange {
+        state: usize,
+        character: char,
+        next_state: usize,
+    },
+
+    /// The task of the cell writes a character which isn't one of the table characters
+    UnknownCharacter {
+        state: usize,
+        character: char,
+        written_character: char,
+    },
+
+    /// The machine can't enter the state when it starts in DEFAULT_STATE
+    UnreachableState { state: usize },
+
+    /// All cells of a normal state are empty
+    DefaultRow { state: usize },
+
+    /// All cells for the character are empty,
+    /// so the machine stops whenever it reads it
+    DefaultColumn { character: char },
+}
+
+impl LintWarning {
+    /// Returns true if the warning concerns the cell of `state` and `character`
+    pub fn is_about_cell(&self, state: usize, character: char) -> bool {
+        match *self {
+            LintWarning::StateOutOfRange {
+                state: s,
+                character: c,
+                ..
+            }
+            | LintWarning::UnknownCharacter {
+                state: s,
+                character: c,
+                ..
+            } => s == state && c == character,
+            _ => false,
+        }
+    }
+
+    /// Returns true if the warning concerns the whole row of `state`
+    pub fn is_about_state(&self, state: usize) -> bool {
+        match *self {
+            LintWarning::UnreachableState { state: s } | LintWarning::DefaultRow { state: s } => {
+                s == state
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns true if the warning concerns the whole column of `character`
+    pub fn is_about_character(&self, character: char) -> bool {
+        matches!(*self, LintWarning::DefaultColumn { character: c } if c == character)
+    }
+}
+
+impl fmt::Display for LintWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintWarning::StateOutOfRange {
+                state,
+                character,
+                next_state,
+            } => write!(
+                f,
+                "state {}, character {}: goes to state {}, which doesn't exist",
+                state, character, next_state
+            ),
+            LintWarning::UnknownCharacter {
+                state,
+                character,
+                written_character,
+            } => write!(
+                f,
+                "state {}, character {}: writes {}, which isn't one of the table characters",
+                state, character, written_character
+            ),
+            LintWarning::UnreachableState { state } => write!(
+                f,
+                "state {}: can't be reached from state {}",
+                state, DEFAULT_STATE
+            ),
+            LintWarning::DefaultRow { state } => {
+                write!(f, "state {}: all tasks are default", state)
+            }
+            LintWarning::DefaultColumn { character } => {
+                write!(f, "character {}: all tasks are default", character)
+            }
+        }
+    }
+}
+
+/// Finds likely mistakes in `table` without running it
+pub fn lint(table: &Table) -> Vec<LintWarning> {
+    let mut warnings = Vec::new();
+    let states_number = table.get_states_number();
+    let characters = table.get_sorted_characters();
+
+    for (state, row) in table.get_tasks().iter().enumerate() {
+        for (task, &character) in row.iter().zip(characters) {
+            if task.is_halting_for(state, character) {
+                continue;
+            }
+
+            if task.state >= states_number {
+                warnings.push(LintWarning::StateOutOfRange {
+                    state,
+                    character,
+                    next_state: task.state,
+                });
+            }
+
+            if !characters.contains(&task.character) {
+                warnings.push(LintWarning::UnknownCharacter {
+                    state,
+                    character,
+                    written_character: task.character,
+                });
+            }
+        }
+    }
+
+    for (state, is_reachable) in get_reachable_states(table).into_iter().enumerate() {
+        if !is_reachable {
+            warnings.push(LintWarning::UnreachableState { state });
+        }
+    }
+
+    // Tasks of accepting and rejecting states are never executed,
+    // so they are expected to be default
+    for (state, row) in table.get_tasks().iter().enumerate() {
+        let is_normal = table.get_state_kind(state) == StateKind::Normal;
+        let is_empty = row
+            .iter()
+            .zip(characters)
+            .all(|(task, &character)| task.is_halting_for(state, character));
+        if is_normal && is_empty {
+            warnings.push(LintWarning::DefaultRow { state });
+        }
+    }
+
+    for (column, &character) in characters.iter().enumerate() {
+        let is_empty = table
+            .get_tasks()
+            .iter()
+            .enumerate()
+            .all(|(state, row)| row[column].is_halting_for(state, character));
+        if is_empty {
+            warnings.push(LintWarning::DefaultColumn { character });
+        }
+    }
+
+    warnings
+}
+
+/// Returns which states can be entered by the machine starting in DEFAULT_STATE.
+/// The index is number of state
+fn get_reachable_states(table: &Table) -> Vec<bool> {
+    let states_number = table.get_states_number();
+    let mut reachable_states = vec![false; states_number];
+    let mut queue = VecDeque::from([DEFAULT_STATE]);
+    reachable_states[DEFAULT_STATE] = true;
+
+    while let Some(state) = queue.pop_front() {
+        // The machine halts as soon as it enters an accepting or rejecting state
+        if table.get_state_kind(state) != StateKind::Normal {
+            continue;
+        }
+
+        for task in &table.get_tasks()[state] {
+            if task.state < states_number && !reachable_states[task.state] {
+                reachable_states[task.state] = true;
+                queue.push_back(task.state);
+            }
+        }
+    }
+
+    reachable_states
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::{Direction, Task};
+
+    use super::*;
+
+    fn task(state: usize, character: char, direction: Direction) -> Task {
+        Task {
+            state,
+            character,
+            direction,
+        }
+    }
+
+    /// Table without any warnings, which moves between its two states
+    fn new_table() -> Table {
+        let mut table = Table::new_empty();
+        table.set_characters("#1");
+        table.set_states_number(2);
+        let tasks = [
+            (0, '#', task(1, '1', Direction::Right)),
+            (0, '1', task(0, '1', Direction::Right)),
+            (1, '#', task(0, '#', Direction::Left)),
+            (1, '1', Task::new_halting(1, '1')),
+        ];
+        for (state, character, task) in tasks {
+            table.set_task_by_state_and_character(task, state, character);
+        }
+        table
+    }
+
+    #[test]
+    fn valid_table_has_no_warnings() {
+        assert_eq!(lint(&new_table()), []);
+    }
+
+    #[test]
+    fn state_out_of_range_is_reported() {
+        let mut table = new_table();
+        table.set_task_by_state_and_character(task(5, '1', Direction::Right), 0, '1');
+
+        assert_eq!(
+            lint(&table),
+            [LintWarning::StateOutOfRange {
+                state: 0,
+                character: '1',
+                next_state: 5
+            }]
+        );
+    }
+
+    #[test]
+    fn unknown_character_is_reported() {
+        let mut table = new_table();
+        table.set_task_by_state_and_character(task(0, 'x', Direction::Right), 0, '1');
+
+        assert_eq!(
+            lint(&table),
+            [LintWarning::UnknownCharacter {
+                state: 0,
+                character: '1',
+                written_character: 'x'
+            }]
+        );
+    }
+
+    #[test]
+    fn unreachable_state_is_reported() {
+        let mut table = new_table();
+        table.set_states_number(3);
+        table.set_task_by_state_and_character(task(0, '1', Direction::Right), 2, '#');
+
+        assert_eq!(lint(&table), [LintWarning::UnreachableState { state: 2 }]);
+    }
+
+    #[test]
+    fn default_row_is_reported_only_for_normal_states() {
+        let mut table = new_table();
+        table.set_task_by_state_and_character(Task::new(), 1, '#');
+        assert_eq!(lint(&table), [LintWarning::DefaultRow { state: 1 }]);
+
+        table.set_state_kind(StateKind::Accepting, 1);
+        assert_eq!(lint(&table), []);
+    }
+
+    #[test]
+    fn default_column_is_reported() {
+        let mut table = new_table();
+        table.set_task_by_state_and_character(Task::new(), 0, '1');
+
+        assert_eq!(
+            lint(&table),
+            [LintWarning::DefaultColumn { character: '1' }]
+        );
+    }
+}
@@ -1,12 +1,71 @@
-use std::time::Duration;
+use core::fmt;
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    constants::{DEFAULT_STATE, MIN_MACHINE_SELF_TIMER_INTERVAL},
-    table::Table,
+    breakpoints::Breakpoints,
+    constants::{DEFAULT_STATE, MAX_UNDO_STEPS},
+    cycle_detector::CycleDetector,
+    table::{StateKind, Table},
     tape::Tape,
     task::Direction,
 };
 
+/// Reason why the machine has finished its work
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HaltReason {
+    /// The cell of the current state and character is default,
+    /// or the character isn't one of the table characters
+    NoRule,
+
+    /// The executed cell had 0 as its direction
+    Stop,
+
+    /// The cursor would go beyond a bounded tape
+    OutOfTape,
+
+    /// The machine entered an accepting state
+    Accepted,
+
+    /// The machine entered a rejecting state
+    Rejected,
+
+    /// The machine repeated a configuration, so it would never halt
+    LoopsForever,
+}
+
+impl fmt::Display for HaltReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            HaltReason::NoRule => "no rule for the current state and character",
+            HaltReason::Stop => "stop direction",
+            HaltReason::OutOfTape => "cursor went beyond the tape",
+            HaltReason::Accepted => "accepted",
+            HaltReason::Rejected => "rejected",
+            HaltReason::LoopsForever => "loops forever",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+/// Configuration of the machine from before a step,
+/// needed to undo that step
+#[derive(Debug, Clone, Copy)]
+pub struct StepDelta {
+    /// State of the machine before the step
+    pub state: usize,
+
+    /// Character under the cursor before the step
+    pub character: char,
+
+    /// Position of the cursor before the step
+    pub cursor_position: isize,
+}
+
+#[derive(Debug, Clone)]
 pub struct Machine {
     // Surrent state of the machine
     state: usize,
@@ -17,10 +76,21 @@ pub struct Machine {
     // Current step
     step: usize,
 
-    // True if machine has finished its work
-    is_halted: bool,
+    // Reason why the machine has finished its work, None if it is still running
+    halt_reason: Option<HaltReason>,
 
-    self_timer_interval: Option<Duration>,
+    // Deltas of up to MAX_UNDO_STEPS last executed steps, the last one is the most recent.
+    // None if the machine doesn't record them, so it can't undo steps
+    history: Option<VecDeque<StepDelta>>,
+
+    // Detector of infinite loops, None if the machine doesn't look for them
+    cycle_detector: Option<CycleDetector>,
+}
+
+impl Default for Machine {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Machine {
@@ -29,8 +99,46 @@ impl Machine {
             state: DEFAULT_STATE,
             tape: Tape::new(),
             step: 0,
-            is_halted: false,
-            self_timer_interval: None,
+            halt_reason: None,
+            history: Some(VecDeque::new()),
+            cycle_detector: None,
+        }
+    }
+
+    /// Creates a machine which doesn't record its steps, so it needs
+    /// constant memory but can't go back, e.g. for running tables from the command line
+    pub fn without_history() -> Self {
+        Self {
+            history: None,
+            ..Self::new()
+        }
+    }
+
+    /// Clones the machine with an empty history and a cycle detector starting
+    /// from the current configuration, so the clone is cheap to move to another thread.
+    /// Its history can be joined back with [`Machine::prepend_history`]
+    pub fn clone_without_history(&self) -> Self {
+        Self {
+            state: self.state,
+            tape: self.tape.clone(),
+            step: self.step,
+            halt_reason: self.halt_reason,
+            history: self.history.as_ref().map(|_| VecDeque::new()),
+            cycle_detector: self
+                .cycle_detector
+                .as_ref()
+                .map(|_| self.new_cycle_detector()),
+        }
+    }
+
+    /// Puts the history of `earlier`, the machine this one was cloned from, before its own.
+    /// Only the last MAX_UNDO_STEPS steps are kept
+    pub fn prepend_history(&mut self, earlier: Machine) {
+        if let (Some(history), Some(mut earlier_history)) = (&mut self.history, earlier.history) {
+            earlier_history.append(history);
+            let overflow = earlier_history.len().saturating_sub(MAX_UNDO_STEPS);
+            earlier_history.drain(..overflow);
+            *history = earlier_history;
         }
     }
 
@@ -38,26 +146,40 @@ impl Machine {
         self.state = DEFAULT_STATE;
         self.tape = new_tape;
         self.step = 0;
-        self.is_halted = false;
-        self.self_timer_interval = None;
+        self.halt_reason = None;
+        if let Some(history) = &mut self.history {
+            history.clear();
+        }
+
+        if let Some(detector) = &mut self.cycle_detector {
+            detector.reset(self.step, self.state, &self.tape);
+        }
+    }
+
+    pub fn is_cycle_detection_enabled(&self) -> bool {
+        self.cycle_detector.is_some()
     }
 
-    pub fn set_self_timer_interval(&mut self, new_interval: Option<u32>) {
-        self.self_timer_interval = match new_interval {
-            Some(0) => Some(Duration::from_millis(
-                MIN_MACHINE_SELF_TIMER_INTERVAL as u64,
-            )),
-            Some(interval) => Some(Duration::from_millis(interval as u64)),
-            None => None,
+    /// Makes the machine halt when it repeats a configuration, starting from the current one
+    pub fn set_cycle_detection(&mut self, enabled: bool) {
+        self.cycle_detector = match enabled {
+            true => Some(self.new_cycle_detector()),
+            false => None,
         };
     }
 
-    pub fn get_self_timer_interval(&self) -> Option<Duration> {
-        self.self_timer_interval
+    fn new_cycle_detector(&self) -> CycleDetector {
+        let mut detector = CycleDetector::new();
+        detector.reset(self.step, self.state, &self.tape);
+        detector
     }
 
     pub fn is_halted(&self) -> bool {
-        self.is_halted
+        self.halt_reason.is_some()
+    }
+
+    pub fn get_halt_reason(&self) -> Option<HaltReason> {
+        self.halt_reason
     }
 
     pub fn get_step(&self) -> usize {
@@ -72,32 +194,271 @@ impl Machine {
         &self.tape
     }
 
+    /// Returns the number of last steps which can be undone,
+    /// 0 if the machine doesn't record its steps
+    pub fn get_undoable_steps(&self) -> usize {
+        self.history.as_ref().map_or(0, |history| history.len())
+    }
+
+    /// Returns true if the next step would execute a cell marked in `breakpoints`
+    pub fn is_at_breakpoint(&self, breakpoints: &Breakpoints) -> bool {
+        !self.is_halted() && breakpoints.is_hit(self.state, self.tape.get_current_char())
+    }
+
     pub fn next_step(&mut self, table: &Table) {
-        if self.is_halted {
+        if self.is_halted() {
+            return;
+        }
+
+        // The machine can start in an accepting or rejecting state
+        if let Some(verdict) = get_verdict(table, self.state) {
+            self.halt_reason = Some(verdict);
             return;
         }
 
         self.step += 1;
 
         let current_char = self.tape.get_current_char();
-        let task = table.get_task(self.state, current_char);
+        let delta = StepDelta {
+            state: self.state,
+            character: current_char,
+            cursor_position: self.tape.get_cursor_position(),
+        };
+        if let Some(history) = &mut self.history {
+            if history.len() == MAX_UNDO_STEPS {
+                history.pop_front();
+            }
+            history.push_back(delta);
+        }
+
+        // Default cells have no rule, so they leave the tape unchanged
+        let task = match table.get_task(self.state, current_char) {
+            Some(task) if !task.is_default() => task,
+            _ => {
+                self.halt_reason = Some(HaltReason::NoRule);
+                return;
+            }
+        };
 
-        if let Some(task) = task {
-            self.tape.set_current_char(task.character);
-            self.state = task.state;
+        self.tape.set_current_char(task.character);
+        self.state = task.state;
 
-            let move_result = match task.direction {
-                Direction::Left => self.tape.move_cursor_left(),
-                Direction::Right => self.tape.move_cursor_right(),
-                Direction::Stop => Err(()),
-            };
+        let move_result = match task.direction {
+            Direction::Left => self.tape.move_cursor_left(),
+            Direction::Right => self.tape.move_cursor_right(),
+            Direction::Stop => Ok(()),
+        };
 
-            if let Ok(_) = move_result {
-                return;
+        self.halt_reason = match (get_verdict(table, self.state), task.direction) {
+            (Some(verdict), _) => Some(verdict),
+            (None, Direction::Stop) => Some(HaltReason::Stop),
+            (None, _) if move_result.is_err() => Some(HaltReason::OutOfTape),
+            (None, _) => None,
+        };
+
+        if let (None, Some(detector)) = (self.halt_reason, &mut self.cycle_detector) {
+            if detector.is_looping(self.state, &self.tape, &delta) {
+                self.halt_reason = Some(HaltReason::LoopsForever);
             }
         }
+    }
+
+    /// Undoes the last executed step if it was recorded
+    pub fn previous_step(&mut self) {
+        let delta = match self.history.as_mut().and_then(|history| history.pop_back()) {
+            Some(delta) => delta,
+            None => return,
+        };
+
+        self.step -= 1;
+        self.state = delta.state;
+        self.tape.set_cursor_position(delta.cursor_position);
+        self.tape.set_current_char(delta.character);
+        self.halt_reason = None;
+
+        if let Some(detector) = &mut self.cycle_detector {
+            detector.rewind(self.step, self.state, &self.tape);
+        }
+    }
+
+    /// Executes or undoes steps until the machine reaches `step`, halts before reaching it
+    /// or has no more recorded steps to undo
+    pub fn go_to_step(&mut self, step: usize, table: &Table) {
+        while self.step > step && self.get_undoable_steps() > 0 {
+            self.previous_step();
+        }
+
+        while self.step < step && !self.is_halted() {
+            self.next_step(table);
+        }
+    }
+}
+
+/// Returns the verdict of entering `state` or None if it is neither accepting nor rejecting
+fn get_verdict(table: &Table, state: usize) -> Option<HaltReason> {
+    match table.get_state_kind(state) {
+        StateKind::Normal => None,
+        StateKind::Accepting => Some(HaltReason::Accepted),
+        StateKind::Rejecting => Some(HaltReason::Rejected),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constants::EMPTY_CHAR,
+        tape::{Tape, TapeMode},
+        task::Task,
+    };
+
+    use super::*;
+
+    fn new_table(tasks: &[(usize, char, Task)]) -> Table {
+        let mut table = Table::new_empty();
+        table.set_characters("#1");
+        table.set_states_number(2);
+        for (state, character, task) in tasks {
+            table.set_task_by_state_and_character(*task, *state, *character);
+        }
+        table
+    }
+
+    fn task(state: usize, character: char, direction: Direction) -> Task {
+        Task {
+            state,
+            character,
+            direction,
+        }
+    }
+
+    /// Runs the table from `chars` and returns the halted machine
+    fn run(table: &Table, chars: &str, mode: TapeMode) -> Machine {
+        let mut tape = Tape::new();
+        tape.set_mode(mode);
+        tape.set_length(chars.len());
+        tape.set_chars(chars.to_string());
+
+        let mut machine = Machine::new();
+        machine.set_cycle_detection(true);
+        machine.reset(tape);
+        while !machine.is_halted() && machine.get_step() < 1000 {
+            machine.next_step(table);
+        }
+        machine
+    }
+
+    fn tape_text(machine: &Machine) -> String {
+        machine
+            .get_tape()
+            .get_chars_without_margin()
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn default_cell_has_no_rule_and_keeps_tape() {
+        let machine = run(&new_table(&[]), "1", TapeMode::Bounded);
+
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::NoRule));
+        assert_eq!(tape_text(&machine), "1");
+    }
+
+    #[test]
+    fn filled_halting_cell_stops() {
+        let table = new_table(&[(0, '1', Task::new_halting(0, '1'))]);
+        let machine = run(&table, "1", TapeMode::Bounded);
+
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::Stop));
+        assert_eq!(machine.get_step(), 1);
+        assert_eq!(tape_text(&machine), "1");
+    }
+
+    #[test]
+    fn unknown_character_has_no_rule() {
+        let machine = run(&new_table(&[]), "x", TapeMode::Bounded);
+
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::NoRule));
+    }
+
+    #[test]
+    fn stop_direction_writes_and_stops() {
+        let table = new_table(&[(0, '1', task(1, EMPTY_CHAR, Direction::Stop))]);
+        let machine = run(&table, "1", TapeMode::Bounded);
+
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::Stop));
+        assert_eq!(machine.get_state(), 1);
+        assert_eq!(tape_text(&machine), EMPTY_CHAR.to_string());
+    }
+
+    #[test]
+    fn bounded_tape_ends() {
+        let table = new_table(&[(0, '1', task(0, '1', Direction::Right))]);
+        let machine = run(&table, "11", TapeMode::Bounded);
+
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::OutOfTape));
+        assert_eq!(machine.get_step(), 2);
+    }
+
+    #[test]
+    fn accepting_and_rejecting_states_halt() {
+        let mut table = new_table(&[(0, '1', task(1, '1', Direction::Right))]);
+
+        table.set_state_kind(StateKind::Accepting, 1);
+        let machine = run(&table, "11", TapeMode::Bounded);
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::Accepted));
+
+        table.set_state_kind(StateKind::Rejecting, 1);
+        let machine = run(&table, "11", TapeMode::Bounded);
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::Rejected));
+    }
+
+    #[test]
+    fn repeated_configuration_loops_forever() {
+        let table = new_table(&[
+            (0, EMPTY_CHAR, task(1, EMPTY_CHAR, Direction::Right)),
+            (1, EMPTY_CHAR, task(0, EMPTY_CHAR, Direction::Left)),
+        ]);
+        let machine = run(&table, "#", TapeMode::Unbounded);
+
+        assert_eq!(machine.get_halt_reason(), Some(HaltReason::LoopsForever));
+    }
+
+    #[test]
+    fn clone_without_history_detects_loops_from_its_start() {
+        let table = new_table(&[
+            (0, EMPTY_CHAR, task(1, EMPTY_CHAR, Direction::Right)),
+            (1, EMPTY_CHAR, task(0, EMPTY_CHAR, Direction::Left)),
+        ]);
+        let mut tape = Tape::new();
+        tape.set_mode(TapeMode::Unbounded);
+        let mut machine = Machine::new();
+        machine.set_cycle_detection(true);
+        machine.reset(tape);
+        machine.next_step(&table);
+
+        let mut clone = machine.clone_without_history();
+        assert!(clone.is_cycle_detection_enabled());
+        clone.go_to_step(10, &table);
+        assert_eq!(clone.get_halt_reason(), Some(HaltReason::LoopsForever));
+        assert_eq!(clone.get_step(), 3);
+    }
+
+    #[test]
+    fn only_last_steps_can_be_undone() {
+        let table = new_table(&[
+            (0, EMPTY_CHAR, task(1, EMPTY_CHAR, Direction::Right)),
+            (1, EMPTY_CHAR, task(0, EMPTY_CHAR, Direction::Left)),
+        ]);
+        let mut tape = Tape::new();
+        tape.set_mode(TapeMode::Unbounded);
+        let mut machine = Machine::new();
+        machine.reset(tape);
+
+        machine.go_to_step(MAX_UNDO_STEPS + 10, &table);
+        assert_eq!(machine.get_undoable_steps(), MAX_UNDO_STEPS);
 
-        self.is_halted = true;
-        self.self_timer_interval = None;
+        machine.go_to_step(0, &table);
+        assert_eq!(machine.get_step(), 10);
+        assert_eq!(machine.get_state(), 0);
     }
 }
@@ -1,22 +1,26 @@
 #![windows_subsystem = "windows"]
-#![feature(iter_array_chunks)]
 
+mod background_run;
+mod blankable_input;
+mod cli;
 mod config;
-mod constants;
 mod dialogs;
 mod focus_actions;
+mod gui_constants;
 mod language;
-mod machine;
 mod my_theme;
 mod numeric_input;
 mod scene;
-mod table;
-mod tape;
-mod task;
+mod space_time_diagram;
+mod tape_animation;
 
+use background_run::{BackgroundRun, BackgroundTests};
 use config::load_config;
-use constants::{ICON_BYTES, ICON_FORMAT};
-use dialogs::error_dialog;
+use dialogs::{describe_parse_error, error_dialog};
+use gui_constants::{
+    DEFAULT_RUN_STEP_BUDGET, ICON_BYTES, ICON_FORMAT, MACHINE_SELF_TIMER_SPEEDS,
+    MAX_UI_THREAD_JUMP, RUN_PROGRESS_INTERVAL,
+};
 use iced::theme::{self, Palette};
 use iced::window::Icon;
 use iced::{
@@ -24,28 +28,53 @@ use iced::{
     Subscription, Theme,
 };
 use iced_native::widget::Id;
+use image::ImageFormat;
 use language::english::ENGLISH_LANGUAGE;
 use language::Language;
-use machine::Machine;
 use my_theme::MyTheme;
 use scene::Scene;
+use space_time_diagram::{SpaceTimeDiagram, DEFAULT_CELL_SIZE, DEFAULT_SPACE_TIME_STEPS};
 use std::env;
 use std::fmt::Display;
 use std::fs::File;
-use std::io::{self, BufReader};
+use std::io;
+use std::mem;
 use std::path::PathBuf;
-use table::Table;
-use tape::Tape;
-use task::Task;
+use std::process;
+use std::time::Duration;
+use tape_animation::{TapeAnimation, DEFAULT_ANIMATION_STEPS, DEFAULT_FRAME_DELAY};
+use turing_machine::{
+    constants::DEFAULT_TEST_MAX_STEPS, formats::dot, Breakpoints, Edit, EditHistory, HaltReason,
+    Machine, Metadata, ParseError, Project, StateKind, Table, Tape, TapeMode, Task, TestCase,
+    TestResult,
+};
 
 use crate::config::save_config;
-use crate::constants::SCALE_FACTOR_STEP;
 use crate::dialogs::{
-    about_program_dialog, pick_file_to_open_dialog, pick_file_to_save_dialog, unsaved_file_dialog,
+    about_program_dialog, lossy_file_dialog, pick_animation_file_to_export_dialog,
+    pick_file_to_open_dialog, pick_file_to_save_dialog,
+    pick_space_time_diagram_file_to_export_dialog, pick_state_diagram_file_to_export_dialog,
+    unsaved_file_dialog,
 };
 use crate::focus_actions::{focus_next, get_focused_element_id};
+use crate::gui_constants::SCALE_FACTOR_STEP;
 
 pub fn main() -> iced::Result {
+    let args: Vec<String> = env::args().collect();
+    let command: Option<fn(&[String]) -> i32> = match args.get(1).map(String::as_str) {
+        Some(cli::RUN_COMMAND) => Some(cli::run),
+        Some(cli::CONVERT_COMMAND) => Some(cli::convert),
+        Some(cli::LINT_COMMAND) => Some(cli::lint_file),
+        Some(cli::TEST_COMMAND) => Some(cli::test),
+        Some(cli::GRADE_COMMAND) => Some(cli::grade),
+        _ => None,
+    };
+
+    if let Some(command) = command {
+        cli::attach_console();
+        process::exit(command(&args[2..]));
+    }
+
     App::run(Settings {
         exit_on_close_request: false,
         window: window::Settings {
@@ -60,7 +89,33 @@ pub fn main() -> iced::Result {
 pub struct App {
     table: Table,
     machine: Machine,
+
+    /// Index in MACHINE_SELF_TIMER_SPEEDS, None if the self-timer is stopped
+    self_timer_speed: Option<usize>,
+
+    /// Run started with "Run to end", None if the machine isn't running in the background
+    background_run: Option<BackgroundRun>,
+
+    /// Generation of the last started background run
+    run_generation: usize,
+    run_step_budget: usize,
+    breakpoints: Breakpoints,
     tape: Tape,
+    metadata: Metadata,
+    tests: Vec<TestCase>,
+
+    /// Results of `tests` in the same order, updated whenever the tests scene is opened.
+    /// None while the test is running in the background
+    test_results: Vec<Option<TestResult>>,
+
+    /// Tests without results running off the UI thread, None if all results are up to date
+    background_tests: Option<BackgroundTests>,
+
+    /// Generation of the last started background tests
+    tests_generation: usize,
+
+    /// Tables, tapes and tests from before edits in the editor
+    history: EditHistory<(Table, Tape, Vec<TestCase>)>,
     file_path: Option<PathBuf>,
     was_modified: bool,
     scene: Scene,
@@ -76,13 +131,36 @@ pub enum Message {
     TapeInputCharsChanged(String),
     TapeInputCursorPositionChanged(isize),
     TapeLengthChanged(usize),
+    TapeModeChanged(TapeMode),
     TableCharactersChanged(String),
     TableStatesNumberChanged(usize),
+    TableStateKindChanged(StateKind, usize),
+    TableStatePositionChanged(Option<(f32, f32)>, usize),
+    TableStatePositionsCleared,
     TableTaskChanged(Task, usize, usize),
+
+    /// Edit made with a single click, undone separately from the edits before and after it
+    SeparateEdit(Box<Message>),
+    StateDragEnded,
+    Undo,
+    Redo,
+    TestsSceneOpened,
+    TestsSceneClosed,
+    TestsRun,
+    TestAdded,
+    TestRemoved(usize),
+    TestNameChanged(String, usize),
+    TestExpectedTapeChanged(String, usize),
+    TestExpectedHaltReasonChanged(Option<HaltReason>, usize),
+    TestMaxStepsChanged(usize, usize),
+    TestsFinished(usize, Option<Vec<(usize, TestResult)>>),
+    BreakpointCellToggled(usize, char),
+    BreakpointStateToggled(usize),
     FileToOpenPicked(Option<PathBuf>),
     FileToSavePicked(Option<PathBuf>),
     LanguageChanged(&'static Language),
-    MachineSelfTimerIntervalChange(Option<u32>),
+    MachineSelfTimerSpeedChanged(Option<usize>),
+    MachineSelfTimerTicked,
     ThemeChanged(MyTheme),
     OpenURL(&'static str),
     AboutProgramClicked,
@@ -91,15 +169,30 @@ pub enum Message {
     OpenFileClicked,
     SaveFileClicked,
     SaveFileAsClicked,
+    ExportStateDiagramClicked,
+    StateDiagramFileToExportPicked(Option<PathBuf>),
+    ExportSpaceTimeDiagramClicked,
+    SpaceTimeDiagramFileToExportPicked(Option<PathBuf>),
+    ExportAnimationClicked,
+    AnimationFileToExportPicked(Option<PathBuf>),
     MachineStarted,
     MachineStopped,
     MachineNextStep,
+    MachinePreviousStep,
+    MachineGoToStep(usize),
+    MachineCycleDetectionToggled(bool),
+    MachineRunToEnd,
+    MachineRunProgressed,
+    MachineRunCancelled,
+    MachineRunFinished(usize, Option<Box<Machine>>),
+    MachineRunStepBudgetChanged(usize),
     CloseButtonClicked,
     FocusedWidget(Option<Id>),
     ErrorDialogClosed(()),
     EventOccurred(iced_native::Event),
     WithUnsavedFileDialog(Box<Message>),
     UnsavedFileDialogAnsweared((bool, Box<Message>)),
+    LossyFileDialogAnsweared((bool, PathBuf, Option<Box<Message>>)),
 }
 
 impl Application for App {
@@ -116,7 +209,18 @@ impl Application for App {
             Self {
                 table: Table::new_empty(),
                 machine: Machine::new(),
+                self_timer_speed: None,
+                background_run: None,
+                run_generation: 0,
+                run_step_budget: DEFAULT_RUN_STEP_BUDGET,
+                breakpoints: Breakpoints::new(),
                 tape: Tape::new(),
+                metadata: Metadata::default(),
+                tests: Vec::new(),
+                test_results: Vec::new(),
+                background_tests: None,
+                tests_generation: 0,
+                history: EditHistory::new(),
                 file_path: None,
                 was_modified: false,
                 scene: Scene::Editor,
@@ -166,16 +270,58 @@ impl Application for App {
                 self.language = language;
                 save_config(self).unwrap_or_else(print_to_stderr);
             }
-            TapeInputCharsChanged(new_chars) => self.tape.set_chars(new_chars),
-            TapeInputCursorPositionChanged(position) => self.tape.set_cursor_position(position),
-            TapeLengthChanged(new_length) => self.tape.set_length(new_length),
+            TapeInputCharsChanged(new_chars) => {
+                self.record_edit(Edit::TapeChars);
+                self.tape.set_chars(new_chars);
+            }
+            TapeInputCursorPositionChanged(position) => {
+                self.record_edit(Edit::TapeCursorPosition);
+                self.tape.set_cursor_position(position);
+            }
+            TapeLengthChanged(new_length) => {
+                self.record_edit(Edit::TapeLength);
+                self.tape.set_length(new_length);
+            }
+            TapeModeChanged(new_mode) => {
+                self.record_edit(Edit::TapeMode);
+                self.tape.set_mode(new_mode);
+            }
+            SeparateEdit(message) => {
+                self.history.finish_edit();
+                let command = self.update(*message);
+                self.history.finish_edit();
+                return command;
+            }
+            StateDragEnded => self.history.finish_edit(),
+            Undo => {
+                if let Some(previous) = self.history.undo(self.get_document()) {
+                    return self.set_document(previous);
+                }
+            }
+            Redo => {
+                if let Some(next) = self.history.redo(self.get_document()) {
+                    return self.set_document(next);
+                }
+            }
             FileToOpenPicked(Some(path)) => return self.open_file(path),
-            FileToSavePicked(Some(path)) => return self.save_file(path),
+            FileToSavePicked(Some(path)) => return self.save_file(path, None),
             OpenFileClicked => return pick_file_to_open_dialog(self.language),
             SaveFileAsClicked => return pick_file_to_save_dialog(self.language),
+            ExportStateDiagramClicked => {
+                return pick_state_diagram_file_to_export_dialog(self.language)
+            }
+            StateDiagramFileToExportPicked(Some(path)) => return self.export_state_diagram(path),
+            ExportSpaceTimeDiagramClicked => {
+                return pick_space_time_diagram_file_to_export_dialog(self.language)
+            }
+            SpaceTimeDiagramFileToExportPicked(Some(path)) => {
+                return self.export_space_time_diagram(path)
+            }
+            ExportAnimationClicked => return pick_animation_file_to_export_dialog(self.language),
+            AnimationFileToExportPicked(Some(path)) => return self.export_animation(path),
             SaveFileClicked => {
                 return match &self.file_path {
-                    Some(path) => self.save_file(path.clone()),
+                    Some(path) => self.save_file(path.clone(), None),
                     None => pick_file_to_save_dialog(self.language),
                 }
             }
@@ -190,36 +336,195 @@ impl Application for App {
                     false => redirect(*callback),
                     true => {
                         if let Some(path) = &self.file_path {
-                            Command::batch([self.save_file(path.clone()), redirect(*callback)])
+                            self.save_file(path.clone(), Some(callback))
                         } else {
                             pick_file_to_save_dialog(self.language)
                         }
                     }
                 }
             }
-            MachineNextStep => self.machine.next_step(&self.table),
+            LossyFileDialogAnsweared((choice, path, callback)) => {
+                if choice {
+                    return self.write_file(path, callback);
+                }
+            }
+            MachineNextStep => {
+                self.machine.next_step(&self.table);
+                if self.machine.is_halted() || self.machine.is_at_breakpoint(&self.breakpoints) {
+                    self.self_timer_speed = None;
+                }
+            }
+            MachinePreviousStep => {
+                self.self_timer_speed = None;
+                self.machine.previous_step();
+            }
+            MachineGoToStep(step) => {
+                self.self_timer_speed = None;
+                let current_step = self.machine.get_step();
+
+                // Like short jumps, long ones don't stop at breakpoints
+                if step > current_step + MAX_UI_THREAD_JUMP {
+                    return self.start_background_run(Breakpoints::new(), step - current_step);
+                }
+                self.machine.go_to_step(step, &self.table);
+            }
+            MachineCycleDetectionToggled(enabled) => self.machine.set_cycle_detection(enabled),
+            MachineRunToEnd => {
+                self.self_timer_speed = None;
+                return self.start_background_run(self.breakpoints.clone(), self.run_step_budget);
+            }
+            // The progress is read from the run when the view is drawn
+            MachineRunProgressed => {}
+            MachineRunCancelled => {
+                if let Some(run) = &self.background_run {
+                    run.cancel();
+                }
+            }
+            // Results of cancelled runs are stale and ignored
+            MachineRunFinished(generation, machine) => {
+                let is_current =
+                    matches!(&self.background_run, Some(run) if run.get_generation() == generation);
+                if is_current {
+                    self.background_run = None;
+                    if let Some(machine) = machine {
+                        let earlier = mem::replace(&mut self.machine, *machine);
+                        self.machine.prepend_history(earlier);
+                    }
+                }
+            }
+            // Results of replaced runs may belong to other tests and are ignored
+            TestsFinished(generation, results) => {
+                let is_current = matches!(
+                    &self.background_tests,
+                    Some(run) if run.get_generation() == generation
+                );
+                if is_current {
+                    self.background_tests = None;
+                    for (index, result) in results.into_iter().flatten() {
+                        self.test_results[index] = Some(result);
+                    }
+                }
+            }
+            MachineRunStepBudgetChanged(budget) => self.run_step_budget = budget,
             MachineStarted => {
+                self.cancel_background_run();
                 self.machine.reset(self.tape.clone());
+                self.self_timer_speed = None;
                 self.scene = Scene::Machine;
             }
             MachineStopped => {
-                self.machine.set_self_timer_interval(None);
+                self.self_timer_speed = None;
+                self.cancel_background_run();
                 self.scene = Scene::Editor
             }
-            MachineSelfTimerIntervalChange(interval) => {
-                self.machine.set_self_timer_interval(interval)
+            TestsSceneOpened => {
+                self.scene = Scene::Tests;
+                return self.run_tests();
+            }
+            TestsSceneClosed => self.scene = Scene::Editor,
+            TestsRun => return self.run_tests(),
+            TestAdded => {
+                let name = format!(
+                    "{} {}",
+                    self.language.tests_scene_texts.default_test_name,
+                    self.tests.len() + 1
+                );
+                let test =
+                    TestCase::new_from_run(name, &self.table, &self.tape, DEFAULT_TEST_MAX_STEPS);
+
+                // A machine which doesn't halt has no result to expect
+                let test = match test {
+                    Some(test) => test,
+                    None => {
+                        return error_dialog(
+                            self.language
+                                .dialog_texts
+                                .add_test_error_description
+                                .to_string(),
+                            self.language,
+                        )
+                    }
+                };
+                self.record_edit(Edit::TestAdded);
+                self.tests.push(test);
+                self.test_results.push(None);
+                return self.run_outdated_tests();
+            }
+            TestRemoved(index) => {
+                self.record_edit(Edit::TestRemoved(index));
+                self.tests.remove(index);
+                self.test_results.remove(index);
+
+                // Running tests are started again, because their indices have changed
+                return self.run_outdated_tests();
+            }
+            TestNameChanged(name, index) => {
+                self.record_edit(Edit::TestName(index));
+                self.tests[index].name = name;
+            }
+            TestExpectedTapeChanged(tape, index) => {
+                self.record_edit(Edit::TestExpectedTape(index));
+                self.tests[index].expected_tape = match tape.is_empty() {
+                    true => None,
+                    false => Some(tape),
+                };
+                return self.rerun_test(index);
+            }
+            TestExpectedHaltReasonChanged(reason, index) => {
+                self.record_edit(Edit::TestExpectedHaltReason(index));
+                self.tests[index].expected_halt_reason = reason;
+                return self.rerun_test(index);
+            }
+            TestMaxStepsChanged(max_steps, index) => {
+                self.record_edit(Edit::TestMaxSteps(index));
+                self.tests[index].max_steps = max_steps;
+                return self.rerun_test(index);
+            }
+            MachineSelfTimerSpeedChanged(speed) => self.self_timer_speed = speed,
+            MachineSelfTimerTicked => {
+                let steps = match self.self_timer_speed {
+                    Some(speed) => MACHINE_SELF_TIMER_SPEEDS[speed].1,
+                    None => 0,
+                };
+
+                for _ in 0..steps {
+                    self.machine.next_step(&self.table);
+                    if self.machine.is_halted() || self.machine.is_at_breakpoint(&self.breakpoints)
+                    {
+                        self.self_timer_speed = None;
+                        break;
+                    }
+                }
             }
             TableCharactersChanged(new_characters) => {
+                self.record_edit(Edit::TableCharacters);
                 self.table.set_characters(&new_characters);
-                self.was_modified = true;
             }
             TableTaskChanged(task, row, column) => {
+                self.record_edit(Edit::Task(row, column));
                 self.table.set_task_by_position(task, row, column);
-                self.was_modified = true;
+            }
+            BreakpointCellToggled(state, character) => {
+                self.breakpoints.toggle_cell(state, character)
+            }
+            BreakpointStateToggled(state) => self.breakpoints.toggle_state(state),
+            TableStateKindChanged(kind, state) => {
+                self.record_edit(Edit::StateKind(state));
+                self.table.set_state_kind(kind, state);
+            }
+            TableStatePositionChanged(position, state) => {
+                self.record_edit(Edit::StatePosition(state));
+                self.table.set_state_position(position, state);
+            }
+            TableStatePositionsCleared => {
+                self.record_edit(Edit::StatePositionsCleared);
+                for state in 0..self.table.get_states_number() {
+                    self.table.set_state_position(None, state);
+                }
             }
             TableStatesNumberChanged(new_states_number) => {
+                self.record_edit(Edit::TableStatesNumber);
                 self.table.set_states_number(new_states_number);
-                self.was_modified = true;
             }
             _ => {}
         };
@@ -232,10 +537,17 @@ impl Application for App {
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        let mut subscriptions = Vec::with_capacity(2);
+        let mut subscriptions = Vec::with_capacity(3);
         subscriptions.push(iced::subscription::events().map(Message::EventOccurred));
-        if let Some(interval) = self.machine.get_self_timer_interval() {
-            subscriptions.push(iced::time::every(interval).map(|_| Message::MachineNextStep))
+        if let Some(speed) = self.self_timer_speed {
+            let interval = Duration::from_millis(MACHINE_SELF_TIMER_SPEEDS[speed].0);
+            subscriptions.push(iced::time::every(interval).map(|_| Message::MachineSelfTimerTicked))
+        }
+        if self.background_run.is_some() {
+            subscriptions.push(
+                iced::time::every(Duration::from_millis(RUN_PROGRESS_INTERVAL))
+                    .map(|_| Message::MachineRunProgressed),
+            )
         }
 
         Subscription::batch(subscriptions)
@@ -251,44 +563,261 @@ impl Application for App {
 }
 
 impl App {
+    /// Runs the machine off the UI thread for at most `step_budget` steps,
+    /// replacing a run which is still in progress
+    fn start_background_run(
+        &mut self,
+        breakpoints: Breakpoints,
+        step_budget: usize,
+    ) -> Command<Message> {
+        self.cancel_background_run();
+        self.run_generation += 1;
+        let generation = self.run_generation;
+        let (run, future) = BackgroundRun::start(
+            self.machine.clone_without_history(),
+            self.table.clone(),
+            breakpoints,
+            step_budget,
+            generation,
+        );
+        self.background_run = Some(run);
+        Command::perform(future, move |machine| {
+            Message::MachineRunFinished(generation, machine.map(Box::new))
+        })
+    }
+
+    /// Stops the background run and discards the machine it would return
+    fn cancel_background_run(&mut self) {
+        if let Some(run) = self.background_run.take() {
+            run.cancel();
+        }
+    }
+
+    /// Saves the table, the tape and the tests before `edit`, so it can be undone
+    fn record_edit(&mut self, edit: Edit) {
+        self.history.record(edit, self.get_document());
+        self.was_modified = true;
+    }
+
+    fn get_document(&self) -> (Table, Tape, Vec<TestCase>) {
+        (self.table.clone(), self.tape.clone(), self.tests.clone())
+    }
+
+    /// Restores the document from the history. Results of the tests are updated
+    /// if they have changed, otherwise they are updated when the tests scene is opened
+    fn set_document(
+        &mut self,
+        (table, tape, tests): (Table, Tape, Vec<TestCase>),
+    ) -> Command<Message> {
+        self.table = table;
+        self.tape = tape;
+        self.was_modified = true;
+        if tests != self.tests {
+            self.tests = tests;
+            return self.run_tests();
+        }
+
+        Command::none()
+    }
+
+    fn run_tests(&mut self) -> Command<Message> {
+        self.test_results = vec![None; self.tests.len()];
+        self.run_outdated_tests()
+    }
+
+    /// Updates the result of the test after it was edited
+    fn rerun_test(&mut self, index: usize) -> Command<Message> {
+        self.test_results[index] = None;
+        self.run_outdated_tests()
+    }
+
+    /// Runs the tests without results off the UI thread,
+    /// replacing the tests which are still running in the background
+    fn run_outdated_tests(&mut self) -> Command<Message> {
+        self.cancel_background_tests();
+        let outdated_tests: Vec<(usize, TestCase)> = self
+            .tests
+            .iter()
+            .cloned()
+            .enumerate()
+            .filter(|(index, _)| self.test_results[*index].is_none())
+            .collect();
+        if outdated_tests.is_empty() {
+            return Command::none();
+        }
+
+        self.tests_generation += 1;
+        let generation = self.tests_generation;
+        let (run, future) = BackgroundTests::start(
+            outdated_tests,
+            self.table.clone(),
+            self.tape.clone(),
+            generation,
+        );
+        self.background_tests = Some(run);
+        Command::perform(future, move |results| {
+            Message::TestsFinished(generation, results)
+        })
+    }
+
+    /// Stops the background tests and discards their results
+    fn cancel_background_tests(&mut self) {
+        if let Some(run) = self.background_tests.take() {
+            run.cancel();
+        }
+    }
+
     fn new_file(&mut self) {
         self.table = Table::new_empty();
+        self.tape = Tape::new();
+        self.metadata = Metadata::default();
+        self.tests.clear();
+        self.test_results.clear();
+        self.cancel_background_tests();
+        self.history.clear();
+        self.breakpoints.clear();
         self.was_modified = false;
         self.file_path = None;
     }
 
     fn open_file(&mut self, path: PathBuf) -> Command<Message> {
         let res = || -> Result<(), io::Error> {
-            let file = File::open(&path)?;
-            let mut buffer = BufReader::new(file);
-            self.table = Table::new_from_buffer(&mut buffer)?;
+            let project = Project::new_from_file(&path)?;
+            self.table = project.table;
+            self.tape = project.tape;
+            self.metadata = project.metadata;
+            self.tests = project.tests;
+            self.history.clear();
+            self.breakpoints.clear();
             self.was_modified = false;
             self.file_path = Some(path);
             Ok(())
         };
 
-        if let Err(_) = res() {
-            return error_dialog(
-                self.language.dialog_texts.open_file_error_description,
-                self.language,
-            );
+        if let Err(e) = res() {
+            let description = self.language.dialog_texts.open_file_error_description;
+            let description = match ParseError::from_io_error(&e) {
+                Some(parse_error) => format!(
+                    "{}\n{}",
+                    description,
+                    describe_parse_error(parse_error, self.language)
+                ),
+                None => description.to_string(),
+            };
+
+            return error_dialog(description, self.language);
         }
 
-        return Command::none();
+        return self.run_tests();
     }
 
-    fn save_file(&mut self, path: PathBuf) -> Command<Message> {
+    fn get_project(&self) -> Project {
+        Project {
+            table: self.table.clone(),
+            tape: self.tape.clone(),
+            metadata: self.metadata.clone(),
+            tests: self.tests.clone(),
+        }
+    }
+
+    /// Saves the file and sends `callback` afterwards,
+    /// asking first if the format of the file drops any part of the project
+    fn save_file(&mut self, path: PathBuf, callback: Option<Box<Message>>) -> Command<Message> {
+        if self.get_project().is_lossy_to_save(&path) {
+            return lossy_file_dialog(path, callback, self.language);
+        }
+
+        self.write_file(path, callback)
+    }
+
+    fn write_file(&mut self, path: PathBuf, callback: Option<Box<Message>>) -> Command<Message> {
+        let callback = match callback {
+            Some(callback) => redirect(*callback),
+            None => Command::none(),
+        };
+
         let res = || -> Result<(), io::Error> {
-            let mut file = File::create(&path)?;
-            self.table.write_to_buffer(&mut file)?;
+            self.get_project().write_to_file(&path)?;
             self.was_modified = false;
             self.file_path = Some(path);
             Ok(())
         };
 
+        if let Err(_) = res() {
+            let dialog = error_dialog(
+                self.language
+                    .dialog_texts
+                    .save_file_error_description
+                    .to_string(),
+                self.language,
+            );
+            return Command::batch([dialog, callback]);
+        }
+
+        callback
+    }
+
+    /// Saves the state diagram of the table without changing the opened file
+    fn export_state_diagram(&self, path: PathBuf) -> Command<Message> {
+        let res = || -> Result<(), io::Error> {
+            let mut file = File::create(path)?;
+            dot::write_table(&self.table, &self.metadata.name, &mut file)
+        };
+
         if let Err(_) = res() {
             return error_dialog(
-                self.language.dialog_texts.save_file_error_description,
+                self.language
+                    .dialog_texts
+                    .export_file_error_description
+                    .to_string(),
+                self.language,
+            );
+        }
+
+        return Command::none();
+    }
+
+    /// Saves the space-time diagram of the run starting with the initial tape
+    fn export_space_time_diagram(&self, path: PathBuf) -> Command<Message> {
+        let diagram = SpaceTimeDiagram::new_from_run(
+            &self.table,
+            self.tape.clone(),
+            DEFAULT_SPACE_TIME_STEPS,
+        );
+        let image = diagram.render(self.table.get_characters(), DEFAULT_CELL_SIZE);
+
+        if let Err(_) = image.save_with_format(path, ImageFormat::Png) {
+            return error_dialog(
+                self.language
+                    .dialog_texts
+                    .export_file_error_description
+                    .to_string(),
+                self.language,
+            );
+        }
+
+        return Command::none();
+    }
+
+    /// Saves the animation of the run starting with the initial tape
+    fn export_animation(&self, path: PathBuf) -> Command<Message> {
+        let animation =
+            TapeAnimation::new_from_run(&self.table, self.tape.clone(), DEFAULT_ANIMATION_STEPS);
+        let texts = &self.language.simulation_scene_texts;
+        let res = animation.save(
+            &path,
+            &self.table,
+            texts.state_text,
+            texts.step_text,
+            DEFAULT_FRAME_DELAY,
+        );
+
+        if let Err(_) = res {
+            return error_dialog(
+                self.language
+                    .dialog_texts
+                    .export_file_error_description
+                    .to_string(),
                 self.language,
             );
         }
@@ -313,6 +842,13 @@ impl App {
                         self.scale_factor -= SCALE_FACTOR_STEP
                     }
                     S if modifiers.control() => return redirect(Message::SaveFileClicked),
+                    // The table can't change while the machine is running
+                    Z if modifiers.control() && !matches!(self.scene, Scene::Machine) => {
+                        return match modifiers.shift() {
+                            true => redirect(Message::Redo),
+                            false => redirect(Message::Undo),
+                        };
+                    }
                     Tab => return focus_next(),
                     _ => {}
                 }
@@ -328,7 +864,10 @@ impl App {
             }
             Mouse(mouse::Event::ButtonReleased(_))
             | Touch(iced::touch::Event::FingerLifted { id: _, position: _ }) => {
-                return get_focused_element_id()
+                // Clicks outside of inputs end typing, so it is undone separately.
+                // Buttons and the state diagram capture their clicks and end edits themselves
+                self.history.finish_edit();
+                return get_focused_element_id();
             }
             _ => {}
         }
@@ -337,6 +876,12 @@ impl App {
     }
 }
 
+/// Wraps `message` of a button or another single click widget,
+/// so its edit is undone separately
+fn separate_edit(message: Message) -> Message {
+    Message::SeparateEdit(Box::new(message))
+}
+
 fn redirect(message: Message) -> Command<Message> {
     async fn noop() {}
     return Command::perform(noop(), |_| message);
@@ -14,6 +14,12 @@ pub struct NumericInput<'a, Message, N, F: Fn(N) -> Message> {
     focused_widget: &'a Option<Id>,
     on_number_change: F,
     can_be_negative: bool,
+
+    /// Typed numbers are sent when Enter is pressed instead of on every change
+    send_on_submit: bool,
+
+    /// Applied to the messages of the + and - buttons
+    wrap_button_message: Option<fn(Message) -> Message>,
 }
 
 pub fn numeric_input<'a, Message, N, F: Fn(N) -> Message>(
@@ -28,6 +34,8 @@ pub fn numeric_input<'a, Message, N, F: Fn(N) -> Message>(
         on_number_change,
         focused_widget,
         can_be_negative: true,
+        send_on_submit: false,
+        wrap_button_message: None,
     }
 }
 
@@ -36,11 +44,22 @@ impl<'a, Message, N, F: Fn(N) -> Message> NumericInput<'a, Message, N, F> {
         self.can_be_negative = value;
         self
     }
+
+    pub fn send_on_submit(mut self, value: bool) -> Self {
+        self.send_on_submit = value;
+        self
+    }
+
+    pub fn wrap_button_message(mut self, wrap: fn(Message) -> Message) -> Self {
+        self.wrap_button_message = Some(wrap);
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum Event {
     InputChanged(String),
+    InputSubmitted,
     IncrementPressed,
     DecrementPressed,
 }
@@ -49,6 +68,9 @@ pub enum DisplayedValue {
     Blank,
     Minus,
     ActualValue,
+
+    /// Number which isn't sent until it is submitted
+    Typed(String),
 }
 
 pub struct State {
@@ -76,8 +98,12 @@ where
     type Event = Event;
 
     fn update(&mut self, state: &mut Self::State, event: Event) -> Option<Message> {
+        let is_button_pressed = matches!(event, Event::IncrementPressed | Event::DecrementPressed);
         let new_val = match event {
             Event::IncrementPressed => self.value + N::one(),
+            Event::DecrementPressed if !self.can_be_negative && self.value.is_zero() => {
+                return None
+            }
             Event::DecrementPressed => self.value - N::one(),
             Event::InputChanged(s) => 'b: {
                 if s.len() == 0 {
@@ -85,15 +111,31 @@ where
                 } else if self.can_be_negative && s == "-" {
                     state.displayed_value = DisplayedValue::Minus;
                 } else if let Ok(new_val) = s.parse() {
+                    if self.send_on_submit {
+                        state.displayed_value = DisplayedValue::Typed(s);
+                        return None;
+                    }
                     state.displayed_value = DisplayedValue::ActualValue;
                     break 'b new_val;
                 }
 
                 return None;
             }
+            Event::InputSubmitted => match &state.displayed_value {
+                DisplayedValue::Typed(s) => {
+                    let new_val = s.parse().ok()?;
+                    state.displayed_value = DisplayedValue::ActualValue;
+                    new_val
+                }
+                _ => return None,
+            },
         };
 
-        Some((self.on_number_change)(new_val))
+        let message = (self.on_number_change)(new_val);
+        match (is_button_pressed, self.wrap_button_message) {
+            (true, Some(wrap)) => Some(wrap(message)),
+            _ => Some(message),
+        }
     }
 
     fn view(&self, state: &Self::State) -> Element<Event, Renderer> {
@@ -102,17 +144,22 @@ where
                 DisplayedValue::Blank => "".to_string(),
                 DisplayedValue::Minus => "-".to_string(),
                 DisplayedValue::ActualValue => self.value.to_string(),
+                DisplayedValue::Typed(s) => s.clone(),
             },
             _ => {
                 self.value.to_string()
             }
         } ;
 
-        let input = text_input(self.placeholder, &input_value, Event::InputChanged)
+        let mut input = text_input(self.placeholder, &input_value, Event::InputChanged)
             .padding(10)
             .size(20)
             .id(state.input_id.clone());
 
+        if self.send_on_submit {
+            input = input.on_submit(Event::InputSubmitted);
+        }
+
         let increment_button = button("+").padding(10).on_press(Event::IncrementPressed);
         let decrement_button = button("-").padding(10).on_press(Event::DecrementPressed);
 
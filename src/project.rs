@@ -0,0 +1,500 @@
+use std::{
+    fs::File,
+    io::{prelude::*, BufReader, Error, ErrorKind},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{
+        BBCHALLENGE_FILE_EXTENSION, DOT_FILE_EXTENSION, FILE_EXTENSION, GV_FILE_EXTENSION,
+        JFLAP_FILE_EXTENSION, JSON_FILE_EXTENSION, YAML_FILE_EXTENSION, YML_FILE_EXTENSION,
+    },
+    formats::{bbchallenge, dot, jflap, json, turingmachine_io},
+    table::{ParseError, Table},
+    tape::{Tape, TapeMode},
+    test_case::TestCase,
+};
+
+const TAPE_CHARS_KEY: &str = "tape_chars";
+const CURSOR_POSITION_KEY: &str = "cursor_position";
+const TAPE_LENGTH_KEY: &str = "tape_length";
+const TAPE_MODE_KEY: &str = "tape_mode";
+const STATE_POSITION_KEY: &str = "state_position";
+const TABLE_KEY: &str = "table";
+const BOUNDED_TAPE_MODE: &str = "bounded";
+const UNBOUNDED_TAPE_MODE: &str = "unbounded";
+
+/// Descriptive information about a project.
+/// It is saved only in the JSON format
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub author: String,
+
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub description: String,
+}
+
+impl Metadata {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_empty() && self.author.is_empty() && self.description.is_empty()
+    }
+}
+
+/// Full editor setup: table of rules and initial tape
+#[derive(Clone)]
+pub struct Project {
+    pub table: Table,
+    pub tape: Tape,
+    pub metadata: Metadata,
+
+    /// Test cases of the table. They are saved only in the JSON format
+    pub tests: Vec<TestCase>,
+}
+
+impl Project {
+    pub fn new_empty() -> Self {
+        Self {
+            table: Table::new_empty(),
+            tape: Tape::new(),
+            metadata: Metadata::default(),
+            tests: Vec::new(),
+        }
+    }
+
+    /// Reads a project with settings of the tape written as `key value` lines,
+    /// followed by `table` line and the table in the same format as in .mt files
+    pub fn new_from_buffer(buffer: &mut impl BufRead) -> Result<Self, Error> {
+        let invalid_data_error = || Error::from(ErrorKind::InvalidData);
+        let mut project = Self::new_empty();
+        let mut tape_chars = String::new();
+        let mut cursor_position = 0;
+        let mut state_positions = Vec::new();
+        let mut lines_number = 0;
+
+        loop {
+            let mut line = String::new();
+            if buffer.read_line(&mut line)? == 0 {
+                return Err(Error::from(ErrorKind::UnexpectedEof));
+            }
+            lines_number += 1;
+
+            let line = line.trim();
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+
+            match key {
+                TAPE_CHARS_KEY => tape_chars = value.to_string(),
+                CURSOR_POSITION_KEY => {
+                    cursor_position = value.parse().or(Err(invalid_data_error()))?
+                }
+                TAPE_LENGTH_KEY => project
+                    .tape
+                    .set_length(value.parse().or(Err(invalid_data_error()))?),
+                TAPE_MODE_KEY => project.tape.set_mode(match value {
+                    BOUNDED_TAPE_MODE => TapeMode::Bounded,
+                    UNBOUNDED_TAPE_MODE => TapeMode::Unbounded,
+                    _ => return Err(invalid_data_error()),
+                }),
+                STATE_POSITION_KEY => {
+                    state_positions.push(parse_state_position(value).ok_or(invalid_data_error())?)
+                }
+                TABLE_KEY => break,
+                "" => {}
+                _ => return Err(invalid_data_error()),
+            }
+        }
+
+        project.table =
+            Table::new_from_buffer(buffer).map_err(|e| offset_parse_error(e, lines_number))?;
+        project.tape.set_chars(tape_chars);
+        project.tape.set_cursor_position(cursor_position);
+
+        for (state, position) in state_positions {
+            project
+                .table
+                .set_state_position(Some(position), state)
+                .ok_or(invalid_data_error())?;
+        }
+
+        Ok(project)
+    }
+
+    pub fn write_to_buffer(&self, buffer: &mut impl Write) -> Result<(), Error> {
+        let tape_chars: String = self.tape.get_chars_without_margin().iter().collect();
+        let tape_mode = match self.tape.get_mode() {
+            TapeMode::Bounded => BOUNDED_TAPE_MODE,
+            TapeMode::Unbounded => UNBOUNDED_TAPE_MODE,
+        };
+
+        writeln!(buffer, "{} {}", TAPE_CHARS_KEY, tape_chars)?;
+        writeln!(
+            buffer,
+            "{} {}",
+            CURSOR_POSITION_KEY,
+            self.tape.get_cursor_position()
+        )?;
+        writeln!(buffer, "{} {}", TAPE_LENGTH_KEY, self.tape.get_length())?;
+        writeln!(buffer, "{} {}", TAPE_MODE_KEY, tape_mode)?;
+
+        for state in 0..self.table.get_states_number() {
+            if let Some((x, y)) = self.table.get_state_position(state) {
+                writeln!(buffer, "{} {} {} {}", STATE_POSITION_KEY, state, x, y)?;
+            }
+        }
+
+        writeln!(buffer, "{}", TABLE_KEY)?;
+
+        self.table.write_to_buffer(buffer)
+    }
+
+    /// Opens a project in the format matching the extension of the file.
+    /// Files with the .mt, .jff and .bb extensions contain only a table of rules,
+    /// so the default tape is used for them
+    pub fn new_from_file(path: &Path) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        Self::read_in_format(get_extension(path), &mut BufReader::new(file))
+    }
+
+    /// Returns true if saving the project to `path` would drop anything which the format
+    /// of the file doesn't keep: tests, metadata, names, comments, positions or kinds of states
+    /// or the tape. The project is written in that format and read back to find out
+    /// what is lost, so only the JSON format keeps all of them
+    pub fn is_lossy_to_save(&self, path: &Path) -> bool {
+        let extension = get_extension(path);
+        let mut buffer = Vec::new();
+
+        // Nothing is dropped if the project can't be written, saving it reports the error
+        if self.write_in_format(extension, &mut buffer).is_err() {
+            return false;
+        }
+
+        match Self::read_in_format(extension, &mut buffer.as_slice()) {
+            Ok(saved) => !self.is_kept_in(&saved),
+            // State diagrams can't be opened again
+            Err(_) => true,
+        }
+    }
+
+    /// Saves the project in the format matching the extension of the file.
+    /// Only the table of rules is saved in .mt, .jff and .bb files
+    /// and .dot and .gv files contain its state diagram
+    pub fn write_to_file(&self, path: &Path) -> Result<(), Error> {
+        let mut file = File::create(path)?;
+        self.write_in_format(get_extension(path), &mut file)
+    }
+
+    fn read_in_format(extension: Option<&str>, buffer: &mut impl BufRead) -> Result<Self, Error> {
+        match extension {
+            Some(FILE_EXTENSION) => Ok(Self {
+                table: Table::new_from_buffer(buffer)?,
+                ..Self::new_empty()
+            }),
+            Some(JSON_FILE_EXTENSION) => json::read_project(buffer),
+            Some(JFLAP_FILE_EXTENSION) => jflap::read_project(buffer),
+            Some(YAML_FILE_EXTENSION | YML_FILE_EXTENSION) => {
+                turingmachine_io::read_project(buffer)
+            }
+            Some(BBCHALLENGE_FILE_EXTENSION) => bbchallenge::read_project(buffer),
+            Some(DOT_FILE_EXTENSION | GV_FILE_EXTENSION) => Err(Error::new(
+                ErrorKind::Unsupported,
+                "state diagrams can only be saved",
+            )),
+            _ => Self::new_from_buffer(buffer),
+        }
+    }
+
+    fn write_in_format(
+        &self,
+        extension: Option<&str>,
+        buffer: &mut impl Write,
+    ) -> Result<(), Error> {
+        match extension {
+            Some(FILE_EXTENSION) => self.table.write_to_buffer(buffer),
+            Some(JSON_FILE_EXTENSION) => json::write_project(self, buffer),
+            Some(JFLAP_FILE_EXTENSION) => jflap::write_project(self, buffer),
+            Some(YAML_FILE_EXTENSION | YML_FILE_EXTENSION) => {
+                turingmachine_io::write_project(self, buffer)
+            }
+            Some(BBCHALLENGE_FILE_EXTENSION) => bbchallenge::write_project(self, buffer),
+            Some(DOT_FILE_EXTENSION | GV_FILE_EXTENSION) => dot::write_project(self, buffer),
+            _ => self.write_to_buffer(buffer),
+        }
+    }
+
+    /// Returns true if `saved`, the project read back after saving this one, has all
+    /// its parts besides the rules, which every format keeps working the same.
+    /// Formats may give names and positions to states which had none
+    /// and add states after the existing ones
+    fn is_kept_in(&self, saved: &Project) -> bool {
+        let (table, saved_table) = (&self.table, &saved.table);
+        let is_state_kept = |state| {
+            let name = table.get_state_name(state);
+            let comment = table.get_state_comment(state);
+            let position = table.get_state_position(state);
+
+            table.get_state_kind(state) == saved_table.get_state_kind(state)
+                && (name.is_empty() || name == saved_table.get_state_name(state))
+                && (comment.is_empty() || comment == saved_table.get_state_comment(state))
+                && (position.is_none() || position == saved_table.get_state_position(state))
+        };
+
+        // Unbounded tapes grow to fit their characters, so only bounded ones keep their length
+        let (tape, saved_tape) = (&self.tape, &saved.tape);
+        let is_tape_kept = tape.get_mode() == saved_tape.get_mode()
+            && tape.get_chars_without_margin() == saved_tape.get_chars_without_margin()
+            && tape.get_cursor_position() == saved_tape.get_cursor_position()
+            && (tape.get_mode() == TapeMode::Unbounded
+                || tape.get_length() == saved_tape.get_length());
+
+        self.tests == saved.tests
+            && self.metadata == saved.metadata
+            && (0..table.get_states_number()).all(is_state_kept)
+            && is_tape_kept
+    }
+}
+
+/// Parses `state x y` value of a state position line
+fn parse_state_position(value: &str) -> Option<(usize, (f32, f32))> {
+    let mut numbers = value.split_whitespace();
+    let state = numbers.next()?.parse().ok()?;
+    let x = numbers.next()?.parse().ok()?;
+    let y = numbers.next()?.parse().ok()?;
+
+    match numbers.next() {
+        Some(_) => None,
+        None => Some((state, (x, y))),
+    }
+}
+
+/// Makes the line of a parse error of the table relative to the start of the project file
+fn offset_parse_error(error: Error, offset: usize) -> Error {
+    match ParseError::from_io_error(&error) {
+        Some(parse_error) => Error::from(ParseError {
+            line: parse_error.line + offset,
+            ..parse_error.clone()
+        }),
+        None => error,
+    }
+}
+
+fn get_extension(path: &Path) -> Option<&str> {
+    path.extension()?.to_str()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        constants::{DEFAULT_TEST_MAX_STEPS, EMPTY_CHAR},
+        table::StateKind,
+        task::{Direction, Task},
+    };
+
+    use super::*;
+
+    /// Adds a part of a project which some formats can't hold
+    type AddPart = fn(&mut Project);
+
+    const PARTS: [(&str, AddPart); 12] = [
+        ("tests", |project| {
+            project.tests.push(TestCase {
+                name: "test".to_string(),
+                tape: "1".to_string(),
+                cursor_position: 0,
+                expected_tape: None,
+                expected_halt_reason: None,
+                max_steps: DEFAULT_TEST_MAX_STEPS,
+            })
+        }),
+        ("metadata", |project| {
+            project.metadata.author = "author".to_string()
+        }),
+        ("state names", |project| {
+            project.table.set_state_name("start".to_string(), 0);
+        }),
+        ("state comments", |project| {
+            project.table.set_state_comment("comment".to_string(), 0);
+        }),
+        ("state positions", |project| {
+            project.table.set_state_position(Some((1.0, 2.0)), 0);
+        }),
+        ("accepting states", |project| {
+            project.table.set_state_kind(StateKind::Accepting, 1);
+        }),
+        ("rejecting states", |project| {
+            project.table.set_state_kind(StateKind::Rejecting, 1);
+        }),
+        ("tape characters", |project| {
+            project.tape.set_chars("1#1".to_string())
+        }),
+        ("cursor position", |project| {
+            project.tape.set_cursor_position(1)
+        }),
+        ("bounded tape", |project| {
+            project.tape.set_mode(TapeMode::Bounded)
+        }),
+        ("unbounded tape", |project| {
+            project.tape.set_mode(TapeMode::Unbounded)
+        }),
+        ("tape length", |project| project.tape.set_length(10)),
+    ];
+
+    fn is_lossy(project: &Project, file_name: &str) -> bool {
+        project.is_lossy_to_save(Path::new(file_name))
+    }
+
+    /// Checks that adding each of PARTS to an empty project with a tape of `tape_mode`
+    /// makes saving it to `file_name` lossy if and only if the part is one of `lost_parts`
+    fn assert_lost_parts(file_name: &str, tape_mode: TapeMode, lost_parts: &[&str]) {
+        let mut empty_project = Project::new_empty();
+        empty_project.tape.set_mode(tape_mode);
+        assert_lost_parts_from(&empty_project, file_name, lost_parts);
+    }
+
+    fn assert_lost_parts_from(empty_project: &Project, file_name: &str, lost_parts: &[&str]) {
+        assert!(!is_lossy(empty_project, file_name), "{}", file_name);
+
+        for (part, add_part) in PARTS {
+            let mut project = empty_project.clone();
+            add_part(&mut project);
+
+            assert_eq!(
+                is_lossy(&project, file_name),
+                lost_parts.contains(&part),
+                "{} in {}",
+                part,
+                file_name
+            );
+        }
+    }
+
+    #[test]
+    fn json_keeps_all_parts() {
+        assert_lost_parts("a.json", TapeMode::Bounded, &[]);
+        assert_lost_parts("a.json", TapeMode::Unbounded, &[]);
+    }
+
+    #[test]
+    fn mtp_loses_tests_metadata_names_and_comments() {
+        let lost_parts = ["tests", "metadata", "state names", "state comments"];
+        assert_lost_parts("a.mtp", TapeMode::Bounded, &lost_parts);
+    }
+
+    #[test]
+    fn mt_loses_all_but_state_kinds() {
+        let lost_parts = [
+            "tests",
+            "metadata",
+            "state names",
+            "state comments",
+            "state positions",
+            "tape characters",
+            "cursor position",
+            "unbounded tape",
+            "tape length",
+        ];
+        assert_lost_parts("a.mt", TapeMode::Bounded, &lost_parts);
+    }
+
+    #[test]
+    fn jff_loses_rejecting_states_and_tape() {
+        let lost_parts = [
+            "tests",
+            "metadata",
+            "state comments",
+            "state positions",
+            "rejecting states",
+            "tape characters",
+            "cursor position",
+            "bounded tape",
+        ];
+        assert_lost_parts("a.jff", TapeMode::Unbounded, &lost_parts);
+    }
+
+    #[test]
+    fn yaml_loses_state_kinds_and_cursor() {
+        let lost_parts = [
+            "tests",
+            "metadata",
+            "state comments",
+            "state positions",
+            "accepting states",
+            "rejecting states",
+            "cursor position",
+            "bounded tape",
+        ];
+        assert_lost_parts("a.yaml", TapeMode::Unbounded, &lost_parts);
+        assert_lost_parts("a.yml", TapeMode::Unbounded, &lost_parts);
+    }
+
+    #[test]
+    fn bb_loses_names_and_tape() {
+        // Rejecting states can't be written at all, so saving them fails instead.
+        // An accepting state is kept only as the last state with a transition going to it
+        let lost_parts = [
+            "tests",
+            "metadata",
+            "state names",
+            "accepting states",
+            "state comments",
+            "state positions",
+            "tape characters",
+            "cursor position",
+            "bounded tape",
+        ];
+        // Only EMPTY_CHAR and digits can be written
+        let mut empty_project = Project::new_empty();
+        empty_project.tape.set_mode(TapeMode::Unbounded);
+        empty_project
+            .table
+            .set_characters(&format!("{}1", EMPTY_CHAR));
+        assert_lost_parts_from(&empty_project, "a.bb", &lost_parts);
+
+        let mut project = empty_project;
+        project.table.set_states_number(2);
+        project.table.set_state_kind(StateKind::Accepting, 1);
+        project.table.set_task_by_state_and_character(
+            Task {
+                state: 1,
+                character: '1',
+                direction: Direction::Right,
+            },
+            0,
+            EMPTY_CHAR,
+        );
+        assert!(!is_lossy(&project, "a.bb"));
+    }
+
+    #[test]
+    fn state_diagrams_lose_everything() {
+        let project = Project::new_empty();
+
+        assert!(is_lossy(&project, "a.dot"));
+        assert!(is_lossy(&project, "a.gv"));
+    }
+
+    #[test]
+    fn empty_project_is_saved_in_table_formats() {
+        let project = Project::new_empty();
+
+        for file_name in ["a.json", "a.mt", "a.mtp"] {
+            assert!(!is_lossy(&project, file_name), "{}", file_name);
+        }
+    }
+
+    #[test]
+    fn parse_error_line_counts_from_start_of_project_file() {
+        let text = "tape_chars 1\ntable\n#1\n01 1 x 00 # 0\n";
+        let error = Project::new_from_buffer(&mut text.as_bytes())
+            .err()
+            .unwrap();
+        let parse_error = ParseError::from_io_error(&error).unwrap();
+
+        assert_eq!(parse_error.line, 4);
+        assert_eq!(parse_error.column, 6);
+    }
+}
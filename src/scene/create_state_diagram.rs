@@ -0,0 +1,413 @@
+use std::cell::{Ref, RefCell};
+
+use iced::{
+    alignment, mouse,
+    widget::canvas::{
+        event, path::Builder, Canvas, Cursor, Event, Frame, Geometry, Path, Program, Stroke, Text,
+    },
+    Color, Element, Length, Point, Rectangle, Theme, Vector,
+};
+use turing_machine::{
+    constants::DEFAULT_STATE,
+    diagram::{get_edges, get_state_positions, Edge},
+    StateKind, Table,
+};
+
+use crate::Message;
+
+const STATE_RADIUS: f32 = 22.0;
+const DIAGRAM_MARGIN: f32 = 60.0;
+const ARROW_SIZE: f32 = 8.0;
+const LABEL_SIZE: f32 = 14.0;
+const STATE_LABEL_SIZE: f32 = 16.0;
+
+/// Distance between the middles of the edges going between two states in both directions
+const CURVE_OFFSET: f32 = 40.0;
+
+/// Draws the state diagram of `table`, whose states can be dragged if it `is_editable`.
+/// The current state and the cell for `current_char` in it are highlighted
+pub fn create_state_diagram<'a>(
+    table: &'a Table,
+    current_state: usize,
+    current_char: Option<char>,
+    is_editable: bool,
+) -> Element<'a, Message> {
+    Canvas::new(StateDiagram {
+        table,
+        current_state,
+        current_char,
+        is_editable,
+    })
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .into()
+}
+
+struct StateDiagram<'a> {
+    table: &'a Table,
+    current_state: usize,
+    current_char: Option<char>,
+    is_editable: bool,
+}
+
+#[derive(Default)]
+struct DiagramState {
+    /// State which is being dragged together with the transform from the start of dragging,
+    /// so the diagram doesn't rescale under the cursor
+    drag: Option<(usize, Transform)>,
+
+    /// Layout of the last drawn table, computed again only after the table changes
+    layout: RefCell<Option<Layout>>,
+}
+
+/// Positions of states and edges of `table`
+struct Layout {
+    table: Table,
+    positions: Vec<(f32, f32)>,
+    edges: Vec<Edge>,
+}
+
+/// Maps positions of states to positions on the canvas
+#[derive(Clone, Copy)]
+struct Transform {
+    scale: f32,
+    translation: Vector,
+}
+
+impl Transform {
+    /// Scales the diagram down to fit in `bounds` and centers it
+    fn fit(positions: &[(f32, f32)], bounds: Rectangle) -> Self {
+        let (min_x, min_y, max_x, max_y) = positions.iter().fold(
+            (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+            |(min_x, min_y, max_x, max_y), &(x, y)| {
+                (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+            },
+        );
+
+        let available_width = (bounds.width - 2.0 * DIAGRAM_MARGIN).max(1.0);
+        let available_height = (bounds.height - 2.0 * DIAGRAM_MARGIN).max(1.0);
+        let scale = (available_width / (max_x - min_x).max(1.0))
+            .min(available_height / (max_y - min_y).max(1.0))
+            .min(1.0);
+
+        let center = Point::new((min_x + max_x) / 2.0, (min_y + max_y) / 2.0);
+        Self {
+            scale,
+            translation: Vector::new(
+                bounds.width / 2.0 - center.x * scale,
+                bounds.height / 2.0 - center.y * scale,
+            ),
+        }
+    }
+
+    fn to_canvas(self, (x, y): (f32, f32)) -> Point {
+        Point::new(x * self.scale, y * self.scale) + self.translation
+    }
+
+    fn to_diagram(self, point: Point) -> (f32, f32) {
+        let point = point - self.translation;
+        (point.x / self.scale, point.y / self.scale)
+    }
+}
+
+impl StateDiagram<'_> {
+    fn get_layout<'s>(&self, state: &'s DiagramState) -> Ref<'s, Layout> {
+        let is_outdated = match &*state.layout.borrow() {
+            Some(layout) => layout.table != *self.table,
+            None => true,
+        };
+
+        if is_outdated {
+            *state.layout.borrow_mut() = Some(Layout {
+                table: self.table.clone(),
+                positions: get_state_positions(self.table),
+                edges: get_edges(self.table),
+            });
+        }
+
+        Ref::map(state.layout.borrow(), |layout| layout.as_ref().unwrap())
+    }
+
+    fn get_transform(&self, state: &DiagramState, bounds: Rectangle) -> Transform {
+        match state.drag {
+            Some((_, transform)) => transform,
+            None => Transform::fit(&self.get_layout(state).positions, bounds),
+        }
+    }
+
+    /// Returns the state under `point` given in canvas coordinates
+    fn get_state_at(
+        &self,
+        state: &DiagramState,
+        transform: &Transform,
+        point: Point,
+    ) -> Option<usize> {
+        self.get_layout(state)
+            .positions
+            .iter()
+            .rposition(|position| transform.to_canvas(*position).distance(point) <= STATE_RADIUS)
+    }
+
+    fn is_edge_highlighted(&self, edge: &Edge) -> bool {
+        edge.from == self.current_state
+            && edge
+                .cells
+                .iter()
+                .any(|(read, _)| Some(*read) == self.current_char)
+    }
+}
+
+impl Program<Message> for StateDiagram<'_> {
+    type State = DiagramState;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> (event::Status, Option<Message>) {
+        let mouse_event = match event {
+            Event::Mouse(mouse_event) if self.is_editable => mouse_event,
+            _ => return (event::Status::Ignored, None),
+        };
+
+        match (mouse_event, state.drag) {
+            (mouse::Event::ButtonPressed(mouse::Button::Left), None) => {
+                let point = match cursor.position_in(&bounds) {
+                    Some(point) => point,
+                    None => return (event::Status::Ignored, None),
+                };
+
+                let transform = self.get_transform(state, bounds);
+                match self.get_state_at(state, &transform, point) {
+                    Some(dragged_state) => {
+                        state.drag = Some((dragged_state, transform));
+                        (event::Status::Captured, None)
+                    }
+                    None => (event::Status::Ignored, None),
+                }
+            }
+            (mouse::Event::CursorMoved { position }, Some((dragged_state, transform))) => {
+                let point = position - Vector::new(bounds.x, bounds.y);
+                let message = Message::TableStatePositionChanged(
+                    Some(transform.to_diagram(point)),
+                    dragged_state,
+                );
+
+                (event::Status::Captured, Some(message))
+            }
+            // The release is captured, so the end of the drag is sent explicitly
+            (mouse::Event::ButtonReleased(mouse::Button::Left), Some(_)) => {
+                state.drag = None;
+                (event::Status::Captured, Some(Message::StateDragEnded))
+            }
+            _ => (event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(
+        &self,
+        state: &Self::State,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let transform = self.get_transform(state, bounds);
+        let layout = self.get_layout(state);
+        let positions: Vec<Point> = layout
+            .positions
+            .iter()
+            .map(|position| transform.to_canvas(*position))
+            .collect();
+        let edges = &layout.edges;
+        let mut frame = Frame::new(bounds.size());
+
+        for edge in edges.iter().filter(|edge| edge.to < positions.len()) {
+            let color = match self.is_edge_highlighted(edge) {
+                true => palette.primary.base.color,
+                false => palette.background.base.text,
+            };
+            let has_reverse_edge = edges
+                .iter()
+                .any(|e| e.from == edge.to && e.to == edge.from && e.from != e.to);
+
+            let label_position = match edge.from == edge.to {
+                true => draw_loop(&mut frame, positions[edge.from], color),
+                false => draw_arrow(
+                    &mut frame,
+                    positions[edge.from],
+                    positions[edge.to],
+                    has_reverse_edge,
+                    color,
+                ),
+            };
+
+            // Labels of loops grow upwards, so they don't cover the state
+            let labels = edge.get_labels();
+            let first_line = match edge.from == edge.to {
+                true => -(labels.len() as f32),
+                false => -(labels.len() as f32) / 2.0,
+            };
+
+            for (i, (label, (read, _))) in labels.into_iter().zip(&edge.cells).enumerate() {
+                let is_current_cell =
+                    edge.from == self.current_state && Some(*read) == self.current_char;
+
+                frame.fill_text(Text {
+                    content: label,
+                    position: label_position
+                        + Vector::new(0.0, (first_line + i as f32 + 0.5) * LABEL_SIZE),
+                    color: match is_current_cell {
+                        true => palette.primary.base.color,
+                        false => palette.background.base.text,
+                    },
+                    size: LABEL_SIZE,
+                    horizontal_alignment: alignment::Horizontal::Center,
+                    vertical_alignment: alignment::Vertical::Center,
+                    ..Text::default()
+                });
+            }
+        }
+
+        // Arrow pointing at the start state
+        let start = positions[DEFAULT_STATE];
+        draw_arrow_line(
+            &mut frame,
+            start - Vector::new(2.5 * STATE_RADIUS, 0.0),
+            start - Vector::new(STATE_RADIUS, 0.0),
+            palette.background.base.text,
+        );
+
+        for (state, &position) in positions.iter().enumerate() {
+            let (fill, text_color) = match state == self.current_state {
+                true => (palette.primary.base.color, palette.primary.base.text),
+                false => (palette.background.base.color, palette.background.base.text),
+            };
+            let border_color = match self.table.get_state_kind(state) {
+                StateKind::Rejecting => palette.danger.base.color,
+                _ => palette.background.base.text,
+            };
+
+            let circle = Path::circle(position, STATE_RADIUS);
+            frame.fill(&circle, fill);
+            frame.stroke(&circle, stroke(border_color));
+
+            if self.table.get_state_kind(state) != StateKind::Normal {
+                frame.stroke(
+                    &Path::circle(position, STATE_RADIUS - 4.0),
+                    stroke(border_color),
+                );
+            }
+
+            let label = match self.table.get_state_name(state) {
+                "" => state.to_string(),
+                name => name.to_string(),
+            };
+
+            frame.fill_text(Text {
+                content: label,
+                position,
+                color: text_color,
+                size: STATE_LABEL_SIZE,
+                horizontal_alignment: alignment::Horizontal::Center,
+                vertical_alignment: alignment::Vertical::Center,
+                ..Text::default()
+            });
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        state: &Self::State,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> mouse::Interaction {
+        if state.drag.is_some() {
+            return mouse::Interaction::Grabbing;
+        }
+        if !self.is_editable {
+            return mouse::Interaction::default();
+        }
+
+        let transform = self.get_transform(state, bounds);
+        match cursor
+            .position_in(&bounds)
+            .and_then(|point| self.get_state_at(state, &transform, point))
+        {
+            Some(_) => mouse::Interaction::Grab,
+            None => mouse::Interaction::default(),
+        }
+    }
+}
+
+/// Draws an arrow between two different states, which is curved if there is
+/// an arrow in the opposite direction, and returns the position of its label
+fn draw_arrow(frame: &mut Frame, from: Point, to: Point, is_curved: bool, color: Color) -> Point {
+    let direction = unit(to - from);
+    let normal = Vector::new(direction.y, -direction.x);
+    let middle = from + (to - from) * 0.5;
+
+    // The middle of a quadratic curve is halfway between its control point and the chord
+    let control = match is_curved {
+        true => middle + normal * CURVE_OFFSET,
+        false => middle,
+    };
+    let start = from + unit(control - from) * STATE_RADIUS;
+    let end = to + unit(control - to) * STATE_RADIUS;
+
+    let mut builder = Builder::new();
+    builder.move_to(start);
+    builder.quadratic_curve_to(control, end);
+    frame.stroke(&builder.build(), stroke(color));
+    draw_arrowhead(frame, end, unit(end - control), color);
+
+    match is_curved {
+        true => middle + normal * (CURVE_OFFSET / 2.0 + LABEL_SIZE),
+        false => middle,
+    }
+}
+
+/// Draws an arrow from a state to itself above the state and returns the position of its label
+fn draw_loop(frame: &mut Frame, position: Point, color: Color) -> Point {
+    let loop_radius = STATE_RADIUS * 0.6;
+    let center = position - Vector::new(0.0, STATE_RADIUS + loop_radius * 0.5);
+    frame.stroke(&Path::circle(center, loop_radius), stroke(color));
+
+    // The loop ends on the right side of the top of the state
+    let end = position + Vector::new(STATE_RADIUS * 0.5, -STATE_RADIUS * 0.87);
+    draw_arrowhead(frame, end, Vector::new(-0.3, 0.95), color);
+
+    center - Vector::new(0.0, loop_radius)
+}
+
+fn draw_arrow_line(frame: &mut Frame, from: Point, to: Point, color: Color) {
+    frame.stroke(&Path::line(from, to), stroke(color));
+    draw_arrowhead(frame, to, unit(to - from), color);
+}
+
+/// Draws the head of an arrow with the tip at `tip` pointing in `direction`
+fn draw_arrowhead(frame: &mut Frame, tip: Point, direction: Vector, color: Color) {
+    let normal = Vector::new(direction.y, -direction.x);
+    let back = tip - direction * ARROW_SIZE;
+
+    let mut builder = Builder::new();
+    builder.move_to(tip);
+    builder.line_to(back + normal * (ARROW_SIZE / 2.0));
+    builder.line_to(back - normal * (ARROW_SIZE / 2.0));
+    builder.close();
+    frame.fill(&builder.build(), color);
+}
+
+fn stroke(color: Color) -> Stroke<'static> {
+    Stroke::default().with_color(color).with_width(1.5)
+}
+
+/// Returns the vector of length 1 with the same direction as `v`
+fn unit(v: Vector) -> Vector {
+    let length = (v.x * v.x + v.y * v.y).sqrt().max(f32::EPSILON);
+    v * (1.0 / length)
+}
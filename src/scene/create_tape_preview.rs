@@ -0,0 +1,44 @@
+use crate::gui_constants::{MAX_TAPE_FONT_SIZE, MIN_TAPE_FONT_SIZE, TAPE_FONT};
+use iced::widget::{column as ui_column, text, Column};
+use std::iter::repeat;
+use turing_machine::{constants::MAX_TAPE_LENGTH, Tape};
+
+pub fn create_tape_preview<'a, Message: 'a + Clone>(tape: &Tape) -> Column<'a, Message> {
+    let cursor_position =
+        (tape.get_first_char_position() as isize + tape.get_cursor_position()) as usize;
+
+    // Unbounded tapes can grow beyond MAX_TAPE_LENGTH,
+    // so only the part around the cursor is shown
+    let (visible_chars, cursor_position) = if tape.get_length() > MAX_TAPE_LENGTH {
+        let first_visible_position = cursor_position
+            .saturating_sub(MAX_TAPE_LENGTH / 2)
+            .min(tape.get_length() - MAX_TAPE_LENGTH);
+        let range = first_visible_position..first_visible_position + MAX_TAPE_LENGTH;
+
+        (
+            &tape.get_chars_with_margin()[range],
+            cursor_position - first_visible_position,
+        )
+    } else {
+        (tape.get_chars_with_margin(), cursor_position)
+    };
+
+    let min_max_font_size_difference = MAX_TAPE_FONT_SIZE - MIN_TAPE_FONT_SIZE;
+    let font_size = MAX_TAPE_FONT_SIZE
+        - (min_max_font_size_difference * visible_chars.len() as u16)
+            / (MAX_TAPE_LENGTH as u16 - 1);
+
+    let margin_left: String = repeat(' ').take(cursor_position).collect();
+
+    ui_column![ui_column![
+        text(visible_chars.iter().collect::<String>())
+            .size(font_size)
+            .font(TAPE_FONT),
+        text(format!("{}^", margin_left))
+            .size(font_size)
+            .font(TAPE_FONT),
+    ]]
+    .align_items(iced::Alignment::Center)
+    .width(iced::Length::Fill)
+    .height(iced::Length::Units(50))
+}
@@ -0,0 +1,354 @@
+use iced::{
+    alignment,
+    theme::{self, palette::Pair},
+    widget::{
+        self, button, column as ui_column, container, horizontal_rule, row,
+        scrollable as ui_scrollable, text, vertical_rule, Container, Row,
+    },
+    Alignment, Background, Element, Length, Theme,
+};
+use iced_native::widget::{scrollable, Id};
+use turing_machine::{
+    constants::{DEFAULT_STATE, EMPTY_CHAR, MAX_STATE_DIGITS},
+    Breakpoints, Direction, LintWarning, StateKind, Table, Task,
+};
+
+use crate::{blankable_input::blankable_input, separate_edit, Message};
+
+const CELL_HEIGHT: u16 = 40;
+const CELL_WIDTH: u16 = 125;
+
+#[derive(PartialEq, Eq)]
+pub struct TasksTableTexts {
+    pub normal_state_label: &'static str,
+    pub accepting_state_label: &'static str,
+    pub rejecting_state_label: &'static str,
+    pub state_out_of_range_warning: &'static str,
+    pub unknown_character_warning: &'static str,
+    pub unreachable_state_warning: &'static str,
+    pub default_row_warning: &'static str,
+    pub default_column_warning: &'static str,
+}
+
+pub fn create_tasks_table<'a>(
+    table: &Table,
+    is_mutable: bool,
+    selected_column: char,
+    selected_row: usize,
+    breakpoints: &Breakpoints,
+    warnings: &[LintWarning],
+    texts: &'static TasksTableTexts,
+    focused_widget: &'a Option<Id>,
+) -> Element<'a, Message> {
+    let mut tasks_table: Row<Message> =
+        row![vertical_rule(0)]
+            .align_items(Alignment::Fill)
+            .width(Length::Units(
+                (1 + table.get_sorted_characters().len() as u16) * CELL_WIDTH,
+            ));
+
+    let mut first_column = ui_column![
+        horizontal_rule(0),
+        table_cell(vec![text(" ").into()], false, false),
+        horizontal_rule(0)
+    ]
+    .align_items(Alignment::Center)
+    .width(Length::FillPortion(1));
+
+    for i in 0..table.get_states_number() {
+        let state_kind = table.get_state_kind(i);
+        let state_kind_label = match state_kind {
+            StateKind::Normal if is_mutable => texts.normal_state_label,
+            StateKind::Normal => "",
+            StateKind::Accepting => texts.accepting_state_label,
+            StateKind::Rejecting => texts.rejecting_state_label,
+        };
+
+        let state_kind_element: Element<_> = if is_mutable {
+            let next_state_kind = match state_kind {
+                StateKind::Normal => StateKind::Accepting,
+                StateKind::Accepting => StateKind::Rejecting,
+                StateKind::Rejecting => StateKind::Normal,
+            };
+
+            button(text(state_kind_label).size(16))
+                .padding(2)
+                .style(theme::Button::Secondary)
+                .on_press(separate_edit(Message::TableStateKindChanged(
+                    next_state_kind,
+                    i,
+                )))
+                .into()
+        } else {
+            text(state_kind_label).into()
+        };
+
+        let mut cell_children = vec![text(i).into()];
+        let state_name = table.get_state_name(i);
+        if !state_name.is_empty() {
+            cell_children.push(text(state_name).size(16).into());
+        }
+        cell_children.push(state_kind_element);
+
+        // The state kind button of the editor can't be nested in another button,
+        // so breakpoints are toggled only in the simulation
+        let cell = table_cell(cell_children, false, breakpoints.is_state_breakpoint(i));
+        let cell = match is_mutable {
+            true => cell.into(),
+            false => clickable_cell(cell, Message::BreakpointStateToggled(i)),
+        };
+        let state_warnings = warnings.iter().filter(|w| w.is_about_state(i));
+
+        first_column = first_column
+            .push(with_warnings(cell, state_warnings, texts))
+            .push(horizontal_rule(0));
+    }
+
+    tasks_table = tasks_table.push(first_column).push(vertical_rule(0));
+
+    for (column_index, char) in table.get_sorted_characters().iter().enumerate() {
+        let header_cell = table_cell(vec![text(char).into()], false, false);
+        let character_warnings = warnings.iter().filter(|w| w.is_about_character(*char));
+
+        let mut col = ui_column![
+            horizontal_rule(0),
+            with_warnings(header_cell, character_warnings, texts),
+            horizontal_rule(0)
+        ]
+        .align_items(Alignment::Center)
+        .width(Length::FillPortion(1));
+
+        for (row_index, row) in table.get_tasks().iter().enumerate() {
+            let task = row[column_index];
+            let is_selected = selected_column == *char && selected_row == row_index;
+            let is_breakpoint = breakpoints.is_hit(row_index, *char);
+
+            let cell: Element<_> = if is_mutable {
+                let on_task_change = move |task: Task| -> Message {
+                    Message::TableTaskChanged(task, row_index, column_index)
+                };
+                mutable_cell(
+                    task,
+                    is_selected,
+                    is_breakpoint,
+                    focused_widget,
+                    on_task_change,
+                )
+                .into()
+            } else {
+                let cell = immutable_cell(task, is_selected, is_breakpoint);
+                clickable_cell(cell, Message::BreakpointCellToggled(row_index, *char))
+            };
+
+            let cell_warnings = warnings
+                .iter()
+                .filter(|w| w.is_about_cell(row_index, *char));
+
+            col = col
+                .push(with_warnings(cell, cell_warnings, texts))
+                .push(horizontal_rule(0));
+        }
+
+        tasks_table = tasks_table.push(col).push(vertical_rule(0));
+    }
+
+    ui_scrollable(tasks_table)
+        .horizontal_scroll(scrollable::Properties::default())
+        .into()
+}
+
+fn immutable_cell<'a>(
+    Task {
+        state,
+        character,
+        direction,
+    }: Task,
+    is_selected: bool,
+    is_breakpoint: bool,
+) -> Container<'a, Message> {
+    table_cell(
+        vec![
+            text(state).width(Length::Units(30)).into(),
+            text(character).width(Length::Units(20)).into(),
+            text(direction).width(Length::Units(10)).into(),
+        ],
+        is_selected,
+        is_breakpoint,
+    )
+}
+
+fn mutable_cell<'a, F: 'a + Clone + Fn(Task) -> Message>(
+    Task {
+        state,
+        character,
+        direction,
+    }: Task,
+    is_selected: bool,
+    is_breakpoint: bool,
+    focused_widget: &'a Option<Id>,
+    on_task_change: F,
+) -> Container<'a, Message> {
+    let c = on_task_change.clone();
+    let update_state = move |state_str: String| {
+        let state: usize = if state_str.len() == 0 {
+            DEFAULT_STATE
+        } else {
+            let state_str: String = state_str.chars().take(MAX_STATE_DIGITS).collect();
+            state_str.parse().unwrap_or(state)
+        };
+        let task = Task {
+            character,
+            direction,
+            state,
+        };
+        c(task)
+    };
+
+    let c = on_task_change.clone();
+    let update_char = move |char_str: String| {
+        let character = match char_str.chars().last() {
+            None => EMPTY_CHAR,
+            Some(c) if c.is_whitespace() => EMPTY_CHAR,
+            Some(c) => c,
+        };
+
+        let task = Task {
+            character,
+            direction,
+            state,
+        };
+        c(task)
+    };
+
+    let update_direction = move |direction_str: String| {
+        let direction = if direction_str.len() == 0 {
+            Direction::Stop
+        } else {
+            direction_str
+                .chars()
+                .last()
+                .unwrap()
+                .try_into()
+                .unwrap_or(direction)
+        };
+        let task = Task {
+            character,
+            direction,
+            state,
+        };
+        on_task_change(task)
+    };
+
+    table_cell(
+        vec![
+            blankable_input(
+                "St",
+                format!("{}", state),
+                focused_widget,
+                Length::Units(30),
+                update_state,
+            )
+            .into(),
+            blankable_input(
+                "C",
+                format!("{}", character),
+                focused_widget,
+                Length::Units(20),
+                update_char,
+            )
+            .into(),
+            blankable_input(
+                "D",
+                format!("{}", direction),
+                focused_widget,
+                Length::Units(10),
+                update_direction,
+            )
+            .into(),
+        ],
+        is_selected,
+        is_breakpoint,
+    )
+}
+
+/// Frames the element and shows the warnings in its tooltip if there are any
+fn with_warnings<'a, 'b>(
+    element: impl Into<Element<'a, Message>>,
+    warnings: impl Iterator<Item = &'b LintWarning>,
+    texts: &'static TasksTableTexts,
+) -> Element<'a, Message> {
+    let warning_texts: Vec<&str> = warnings
+        .map(|warning| match warning {
+            LintWarning::StateOutOfRange { .. } => texts.state_out_of_range_warning,
+            LintWarning::UnknownCharacter { .. } => texts.unknown_character_warning,
+            LintWarning::UnreachableState { .. } => texts.unreachable_state_warning,
+            LintWarning::DefaultRow { .. } => texts.default_row_warning,
+            LintWarning::DefaultColumn { .. } => texts.default_column_warning,
+        })
+        .collect();
+
+    if warning_texts.is_empty() {
+        return element.into();
+    }
+
+    let framed_element = container(element)
+        .width(Length::Fill)
+        .style(theme::Container::from(
+            warning_appearance as fn(&Theme) -> container::Appearance,
+        ));
+
+    widget::tooltip(
+        framed_element,
+        warning_texts.join("\n"),
+        widget::tooltip::Position::FollowCursor,
+    )
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn warning_appearance(theme: &Theme) -> container::Appearance {
+    container::Appearance {
+        border_width: 2.0,
+        border_color: theme.extended_palette().danger.base.color,
+        ..Default::default()
+    }
+}
+
+/// Makes the cell clickable without changing its appearance
+fn clickable_cell<'a>(cell: Container<'a, Message>, on_press: Message) -> Element<'a, Message> {
+    button(cell)
+        .padding(0)
+        .width(Length::Fill)
+        .style(theme::Button::Text)
+        .on_press(on_press)
+        .into()
+}
+
+fn table_cell<'a>(
+    children: Vec<Element<'a, Message>>,
+    is_selected: bool,
+    is_breakpoint: bool,
+) -> Container<'a, Message> {
+    let f: Option<fn(&Theme) -> container::Appearance> = match (is_selected, is_breakpoint) {
+        (false, false) => None,
+        (true, false) => Some(|t| cell_appearance(t.extended_palette().background.strong)),
+        (false, true) => Some(|t| cell_appearance(t.extended_palette().danger.weak)),
+        (true, true) => Some(|t| cell_appearance(t.extended_palette().danger.strong)),
+    };
+    let theme = f.map(theme::Container::from).unwrap_or_default();
+
+    container(Row::with_children(children).spacing(5))
+        .align_x(alignment::Horizontal::Center)
+        .align_y(alignment::Vertical::Center)
+        .height(Length::Units(CELL_HEIGHT))
+        .width(Length::Fill)
+        .style(theme)
+}
+
+fn cell_appearance(Pair { color, text }: Pair) -> container::Appearance {
+    widget::container::Appearance {
+        background: Some(Background::Color(color)),
+        text_color: Some(text),
+        ..Default::default()
+    }
+}
@@ -1,14 +1,14 @@
-use crate::{
-    constants::DEFAULT_STATE, numeric_input::numeric_input,
-    table::create_tasks_table::create_tasks_table, tape::create_tape_preview::create_tape_preview,
-    App, Message,
-};
+use crate::{numeric_input::numeric_input, separate_edit, App, Message};
 use iced::{
-    widget::{button, column as ui_column, text_input},
+    widget::{button, checkbox, column as ui_column, row, text_input},
     Element, Length,
 };
+use turing_machine::{constants::DEFAULT_STATE, lint, TapeMode};
 
-use super::scene_frame;
+use super::{
+    create_state_diagram::create_state_diagram, create_tape_preview::create_tape_preview,
+    create_tasks_table::create_tasks_table, scene_frame,
+};
 
 #[derive(PartialEq, Eq)]
 pub struct EditorSceneTexts {
@@ -18,11 +18,14 @@ pub struct EditorSceneTexts {
     pub initial_cursor_position_input_label: &'static str,
     pub tape_length_input_placeholder: &'static str,
     pub tape_length_input_label: &'static str,
+    pub unbounded_tape_checkbox_label: &'static str,
     pub table_characters_input_placeholder: &'static str,
     pub table_characters_input_label: &'static str,
     pub table_states_number_input_placeholder: &'static str,
     pub table_states_number_input_label: &'static str,
     pub start_machine_button_text: &'static str,
+    pub arrange_state_diagram_button_text: &'static str,
+    pub tests_button_text: &'static str,
 }
 
 pub fn editor_scene<'a>(app: &'a App) -> Element<'a, Message> {
@@ -33,12 +36,26 @@ pub fn editor_scene<'a>(app: &'a App) -> Element<'a, Message> {
         true,
         app.tape.get_current_char(),
         DEFAULT_STATE,
+        &app.breakpoints,
+        &lint(&app.table),
+        &app.language.tasks_table_texts,
         &app.focused_widget,
     );
 
+    let state_diagram = create_state_diagram(
+        &app.table,
+        DEFAULT_STATE,
+        Some(app.tape.get_current_char()),
+        true,
+    );
+
     let tape_preview = create_tape_preview(&app.tape);
 
-    scene_frame(tape_preview.into(), left_column.into(), tasks_table.into())
+    scene_frame(
+        tape_preview.into(),
+        left_column.into(),
+        row![tasks_table, state_diagram].spacing(20).into(),
+    )
 }
 
 fn left_column<'a>(app: &'a App) -> Element<'a, Message> {
@@ -49,16 +66,22 @@ fn left_column<'a>(app: &'a App) -> Element<'a, Message> {
         initial_cursor_position_input_label,
         tape_length_input_placeholder,
         tape_length_input_label,
+        unbounded_tape_checkbox_label,
         table_characters_input_placeholder,
         table_characters_input_label,
         table_states_number_input_placeholder,
         table_states_number_input_label,
         start_machine_button_text,
+        arrange_state_diagram_button_text,
+        tests_button_text,
     } = app.language.editor_scene_texts;
 
     let initial_tape_input = text_input(
         initial_tape_input_placeholder,
-        app.tape.get_chars_without_margin(),
+        &app.tape
+            .get_chars_without_margin()
+            .iter()
+            .collect::<String>(),
         Message::TapeInputCharsChanged,
     )
     .padding(10)
@@ -69,7 +92,8 @@ fn left_column<'a>(app: &'a App) -> Element<'a, Message> {
         app.tape.get_cursor_position(),
         &app.focused_widget,
         Message::TapeInputCursorPositionChanged,
-    );
+    )
+    .wrap_button_message(separate_edit);
 
     let tape_length_input = numeric_input(
         tape_length_input_placeholder,
@@ -77,7 +101,19 @@ fn left_column<'a>(app: &'a App) -> Element<'a, Message> {
         &app.focused_widget,
         Message::TapeLengthChanged,
     )
-    .can_be_negative(false);
+    .can_be_negative(false)
+    .wrap_button_message(separate_edit);
+
+    let unbounded_tape_checkbox = checkbox(
+        unbounded_tape_checkbox_label,
+        app.tape.get_mode() == TapeMode::Unbounded,
+        |is_checked| {
+            separate_edit(Message::TapeModeChanged(match is_checked {
+                true => TapeMode::Unbounded,
+                false => TapeMode::Bounded,
+            }))
+        },
+    );
 
     let table_characters_input = text_input(
         table_characters_input_placeholder,
@@ -93,18 +129,30 @@ fn left_column<'a>(app: &'a App) -> Element<'a, Message> {
         &app.focused_widget,
         Message::TableStatesNumberChanged,
     )
-    .can_be_negative(false);
+    .can_be_negative(false)
+    .wrap_button_message(separate_edit);
 
     let start_button = button(start_machine_button_text)
         .padding(10)
         .width(Length::Fill)
         .on_press(Message::MachineStarted);
 
+    let arrange_state_diagram_button = button(arrange_state_diagram_button_text)
+        .padding(10)
+        .width(Length::Fill)
+        .on_press(separate_edit(Message::TableStatePositionsCleared));
+
+    let tests_button = button(tests_button_text)
+        .padding(10)
+        .width(Length::Fill)
+        .on_press(Message::TestsSceneOpened);
+
     ui_column![
         initial_tape_input_label,
         initial_tape_input,
         tape_length_input_label,
         tape_length_input,
+        unbounded_tape_checkbox,
         initial_cursor_position_input_label,
         initial_cursor_position_input,
         table_states_number_input_label,
@@ -112,6 +160,8 @@ fn left_column<'a>(app: &'a App) -> Element<'a, Message> {
         table_characters_input_label,
         table_characters_input,
         start_button,
+        arrange_state_diagram_button,
+        tests_button,
     ]
     .width(Length::Units(200))
     .spacing(10)
@@ -1,10 +1,16 @@
+mod create_state_diagram;
+mod create_tape_preview;
+mod create_tasks_table;
 mod editor_scene;
 mod side_column;
 mod simulation_scene;
+mod tests_scene;
 
+pub use create_tasks_table::TasksTableTexts;
 pub use editor_scene::EditorSceneTexts;
 pub use side_column::SideColumnTexts;
 pub use simulation_scene::SimulationSceneTexts;
+pub use tests_scene::TestsSceneTexts;
 
 use crate::{App, Message};
 use iced::{
@@ -12,11 +18,15 @@ use iced::{
     Element, Length,
 };
 
-use self::{editor_scene::editor_scene, side_column::side_column, simulation_scene::machine_scene};
+use self::{
+    editor_scene::editor_scene, side_column::side_column, simulation_scene::machine_scene,
+    tests_scene::tests_scene,
+};
 
 pub enum Scene {
     Editor,
     Machine,
+    Tests,
 }
 
 impl Scene {
@@ -24,6 +34,7 @@ impl Scene {
         let scene = match self {
             Self::Editor => editor_scene(app),
             Self::Machine => machine_scene(app),
+            Self::Tests => tests_scene(app),
         };
         let side_column = side_column(app);
 
@@ -4,7 +4,7 @@ use iced::{
     Element, Length,
 };
 
-use crate::{constants::ICON_BYTES, language::ALL_LANGUAGES, my_theme::MyTheme, App, Message};
+use crate::{gui_constants::ICON_BYTES, language::ALL_LANGUAGES, my_theme::MyTheme, App, Message};
 
 #[derive(PartialEq, Eq)]
 pub struct SideColumnTexts {
@@ -12,6 +12,9 @@ pub struct SideColumnTexts {
     pub open_file_button_text: &'static str,
     pub save_file_button_text: &'static str,
     pub save_file_as_button_text: &'static str,
+    pub export_state_diagram_button_text: &'static str,
+    pub export_space_time_diagram_button_text: &'static str,
+    pub export_animation_button_text: &'static str,
     pub help_button_text: &'static str,
     pub help_url: &'static str,
     pub about_program_button_text: &'static str,
@@ -24,6 +27,9 @@ pub fn side_column<'a>(app: &'a App) -> Element<'a, Message> {
         open_file_button_text,
         save_file_button_text,
         save_file_as_button_text,
+        export_state_diagram_button_text,
+        export_space_time_diagram_button_text,
+        export_animation_button_text,
         help_button_text,
         help_url,
         about_program_button_text,
@@ -75,6 +81,21 @@ pub fn side_column<'a>(app: &'a App) -> Element<'a, Message> {
             .width(Length::Fill)
             .on_press(Message::SaveFileAsClicked);
 
+        let export_state_diagram_button = button(export_state_diagram_button_text)
+            .padding(10)
+            .width(Length::Fill)
+            .on_press(Message::ExportStateDiagramClicked);
+
+        let export_space_time_diagram_button = button(export_space_time_diagram_button_text)
+            .padding(10)
+            .width(Length::Fill)
+            .on_press(Message::ExportSpaceTimeDiagramClicked);
+
+        let export_animation_button = button(export_animation_button_text)
+            .padding(10)
+            .width(Length::Fill)
+            .on_press(Message::ExportAnimationClicked);
+
         let help_button = button(help_button_text)
             .padding(10)
             .width(Length::Fill)
@@ -112,6 +133,9 @@ pub fn side_column<'a>(app: &'a App) -> Element<'a, Message> {
             open_file_button,
             save_file_button,
             save_file_as_button,
+            export_state_diagram_button,
+            export_space_time_diagram_button,
+            export_animation_button,
             help_button,
             about_program_button,
             language_picker_label,
@@ -1,25 +1,42 @@
 use crate::{
-    constants::{MACHINE_SELF_TIMER_INTERVAL_STEP, STOP_MACHINE_SELF_TIMER_VALUE},
-    table::create_tasks_table::create_tasks_table,
-    tape::create_tape_preview::create_tape_preview,
-    App, Message,
+    background_run::BackgroundRun, gui_constants::MACHINE_SELF_TIMER_SPEEDS,
+    numeric_input::numeric_input, App, Message,
 };
 use iced::{
-    widget::{button, column as ui_column, slider, text},
+    widget::{button, checkbox, column as ui_column, progress_bar, row, slider, text},
     Element, Length,
 };
+use turing_machine::HaltReason;
 
-use super::scene_frame;
+use super::{
+    create_state_diagram::create_state_diagram, create_tape_preview::create_tape_preview,
+    create_tasks_table::create_tasks_table, scene_frame,
+};
 
 #[derive(PartialEq, Eq)]
 pub struct SimulationSceneTexts {
-    pub machine_halted_text: &'static str,
-    pub self_timer_interval_none_text: &'static str,
-    pub self_timer_interval_input_label: &'static str,
+    pub no_rule_halt_text: &'static str,
+    pub stop_halt_text: &'static str,
+    pub out_of_tape_halt_text: &'static str,
+    pub accepted_halt_text: &'static str,
+    pub rejected_halt_text: &'static str,
+    pub loops_forever_halt_text: &'static str,
+    pub detect_loops_checkbox_label: &'static str,
+    pub self_timer_speed_none_text: &'static str,
+    pub self_timer_speed_input_label: &'static str,
+    pub steps_per_second_text: &'static str,
     pub stop_machine_button_text: &'static str,
     pub next_step_button_text: &'static str,
+    pub previous_step_button_text: &'static str,
+    pub go_to_step_input_placeholder: &'static str,
+    pub go_to_step_input_label: &'static str,
     pub step_text: &'static str,
     pub state_text: &'static str,
+    pub run_to_end_button_text: &'static str,
+    pub run_step_budget_input_label: &'static str,
+    pub run_step_budget_input_placeholder: &'static str,
+    pub running_text: &'static str,
+    pub cancel_run_button_text: &'static str,
 }
 
 pub fn machine_scene<'a>(app: &'a App) -> Element<'a, Message> {
@@ -30,23 +47,56 @@ pub fn machine_scene<'a>(app: &'a App) -> Element<'a, Message> {
         false,
         app.machine.get_tape().get_current_char(),
         app.machine.get_state(),
+        &app.breakpoints,
+        &[],
+        &app.language.tasks_table_texts,
         &app.focused_widget,
     );
 
+    // The halted machine won't execute any more cells
+    let current_char = match app.machine.is_halted() {
+        true => None,
+        false => Some(app.machine.get_tape().get_current_char()),
+    };
+    let state_diagram =
+        create_state_diagram(&app.table, app.machine.get_state(), current_char, false);
+
     let tape_preview = create_tape_preview(app.machine.get_tape());
 
-    scene_frame(tape_preview.into(), left_column.into(), tasks_table.into())
+    scene_frame(
+        tape_preview.into(),
+        left_column.into(),
+        row![tasks_table, state_diagram].spacing(20).into(),
+    )
 }
 
-fn left_column<'a>(app: &App) -> Element<'a, Message> {
+fn left_column<'a>(app: &'a App) -> Element<'a, Message> {
+    if let Some(run) = &app.background_run {
+        return background_run_column(app, run);
+    }
+
     let SimulationSceneTexts {
-        machine_halted_text,
-        self_timer_interval_none_text,
-        self_timer_interval_input_label,
+        no_rule_halt_text,
+        stop_halt_text,
+        out_of_tape_halt_text,
+        accepted_halt_text,
+        rejected_halt_text,
+        loops_forever_halt_text,
+        detect_loops_checkbox_label,
+        self_timer_speed_none_text,
+        self_timer_speed_input_label,
+        steps_per_second_text,
         stop_machine_button_text,
         next_step_button_text,
+        previous_step_button_text,
+        go_to_step_input_placeholder,
+        go_to_step_input_label,
         step_text,
         state_text,
+        run_to_end_button_text,
+        run_step_budget_input_label,
+        run_step_budget_input_placeholder,
+        ..
     } = app.language.simulation_scene_texts;
 
     let stop_button = button(stop_machine_button_text)
@@ -57,39 +107,70 @@ fn left_column<'a>(app: &App) -> Element<'a, Message> {
     let step = text(format!("{}: {}", step_text, app.machine.get_step()));
     let state = text(format!("{}: {}", state_text, app.machine.get_state()));
 
-    let next_step_button: Element<_> = if app.machine.is_halted() {
-        text(machine_halted_text).into()
+    let go_to_step_input = numeric_input(
+        go_to_step_input_placeholder,
+        app.machine.get_step(),
+        &app.focused_widget,
+        Message::MachineGoToStep,
+    )
+    .can_be_negative(false)
+    .send_on_submit(true);
+
+    let mut previous_step_button = button(previous_step_button_text)
+        .padding(10)
+        .width(Length::Fill);
+
+    if app.machine.get_step() > 0 {
+        previous_step_button = previous_step_button.on_press(Message::MachinePreviousStep);
+    }
+
+    let next_step_button: Element<_> = if let Some(halt_reason) = app.machine.get_halt_reason() {
+        let halt_text = match halt_reason {
+            HaltReason::NoRule => no_rule_halt_text,
+            HaltReason::Stop => stop_halt_text,
+            HaltReason::OutOfTape => out_of_tape_halt_text,
+            HaltReason::Accepted => accepted_halt_text,
+            HaltReason::Rejected => rejected_halt_text,
+            HaltReason::LoopsForever => loops_forever_halt_text,
+        };
+        text(halt_text).into()
     } else {
-        let slider_val = if let Some(interval) = app.machine.get_self_timer_interval() {
-            interval.as_millis() as u32
-        } else {
-            STOP_MACHINE_SELF_TIMER_VALUE
+        // The leftmost value of the slider stops the self-timer and the next ones are the speeds
+        let slider_val = match app.self_timer_speed {
+            Some(speed) => speed as u32 + 1,
+            None => 0,
         };
 
         let on_slider_change = |v: u32| {
-            let c = match v {
-                STOP_MACHINE_SELF_TIMER_VALUE => None,
-                v => Some(v),
+            let speed = match v {
+                0 => None,
+                v => Some(v as usize - 1),
             };
-            Message::MachineSelfTimerIntervalChange(c)
+            Message::MachineSelfTimerSpeedChanged(speed)
         };
 
-        let slider_val_text = match slider_val {
-            STOP_MACHINE_SELF_TIMER_VALUE => self_timer_interval_none_text.to_string(),
-            v => format!("{}ms", v),
+        let slider_val_text = match app.self_timer_speed {
+            Some(speed) => {
+                let (interval, steps) = MACHINE_SELF_TIMER_SPEEDS[speed];
+                format!(
+                    "{} {}",
+                    steps as u64 * 1000 / interval,
+                    steps_per_second_text
+                )
+            }
+            None => self_timer_speed_none_text.to_string(),
         };
 
         ui_column![
             text(format!(
                 "{}: {}",
-                self_timer_interval_input_label, slider_val_text
+                self_timer_speed_input_label, slider_val_text
             )),
             slider(
-                0..=STOP_MACHINE_SELF_TIMER_VALUE,
+                0..=MACHINE_SELF_TIMER_SPEEDS.len() as u32,
                 slider_val,
                 on_slider_change
-            )
-            .step(MACHINE_SELF_TIMER_INTERVAL_STEP),
+            ),
             button(next_step_button_text)
                 .padding(10)
                 .width(Length::Fill)
@@ -98,8 +179,90 @@ fn left_column<'a>(app: &App) -> Element<'a, Message> {
         .into()
     };
 
-    ui_column![step, state, next_step_button, stop_button]
+    let detect_loops_checkbox = checkbox(
+        detect_loops_checkbox_label,
+        app.machine.is_cycle_detection_enabled(),
+        Message::MachineCycleDetectionToggled,
+    );
+
+    let mut column = ui_column![
+        step,
+        state,
+        go_to_step_input_label,
+        go_to_step_input,
+        next_step_button
+    ];
+
+    if !app.machine.is_halted() {
+        let run_step_budget_input = numeric_input(
+            run_step_budget_input_placeholder,
+            app.run_step_budget,
+            &app.focused_widget,
+            Message::MachineRunStepBudgetChanged,
+        )
+        .can_be_negative(false);
+
+        let run_to_end_button = button(run_to_end_button_text)
+            .padding(10)
+            .width(Length::Fill)
+            .on_press(Message::MachineRunToEnd);
+
+        column = column
+            .push(text(run_step_budget_input_label))
+            .push(run_step_budget_input)
+            .push(run_to_end_button);
+    }
+
+    column
+        .push(previous_step_button)
+        .push(detect_loops_checkbox)
+        .push(stop_button)
         .width(Length::Units(200))
         .spacing(10)
         .into()
 }
+
+/// Shows the progress of the machine running in the background and a button to cancel it
+fn background_run_column<'a>(app: &'a App, run: &BackgroundRun) -> Element<'a, Message> {
+    let SimulationSceneTexts {
+        step_text,
+        running_text,
+        cancel_run_button_text,
+        stop_machine_button_text,
+        ..
+    } = app.language.simulation_scene_texts;
+
+    let executed_steps = run.get_executed_steps();
+    let step = text(format!(
+        "{}: {}",
+        step_text,
+        app.machine.get_step() + executed_steps
+    ));
+    let progress = text(format!(
+        "{}: {}/{}",
+        running_text,
+        executed_steps,
+        run.get_step_budget()
+    ));
+
+    let cancel_run_button = button(cancel_run_button_text)
+        .padding(10)
+        .width(Length::Fill)
+        .on_press(Message::MachineRunCancelled);
+
+    let stop_button = button(stop_machine_button_text)
+        .padding(10)
+        .width(Length::Fill)
+        .on_press(Message::MachineStopped);
+
+    ui_column![
+        step,
+        progress,
+        progress_bar(0.0..=run.get_step_budget() as f32, executed_steps as f32),
+        cancel_run_button,
+        stop_button
+    ]
+    .width(Length::Units(200))
+    .spacing(10)
+    .into()
+}
@@ -0,0 +1,252 @@
+use core::fmt;
+
+use crate::{numeric_input::numeric_input, separate_edit, App, Message};
+use iced::{
+    theme,
+    widget::{
+        button, column as ui_column, container, pick_list, row, scrollable, text, text_input,
+    },
+    Alignment, Element, Length,
+};
+use turing_machine::{HaltReason, TestCase, TestResult};
+
+use super::scene_frame;
+
+#[derive(PartialEq, Eq)]
+pub struct TestsSceneTexts {
+    pub run_tests_button_text: &'static str,
+    pub add_test_button_text: &'static str,
+    pub add_test_description: &'static str,
+    pub close_tests_button_text: &'static str,
+    pub remove_test_button_text: &'static str,
+    pub test_name_input_placeholder: &'static str,
+    pub default_test_name: &'static str,
+    pub passed_tests_text: &'static str,
+    pub passed_text: &'static str,
+    pub failed_text: &'static str,
+    pub running_text: &'static str,
+    pub input_text: &'static str,
+    pub cursor_position_text: &'static str,
+    pub tape_text: &'static str,
+    pub halt_reason_text: &'static str,
+    pub expected_text: &'static str,
+    pub steps_text: &'static str,
+    pub max_steps_text: &'static str,
+    pub not_checked_text: &'static str,
+    pub step_limit_reached_text: &'static str,
+    pub no_rule_halt_text: &'static str,
+    pub stop_halt_text: &'static str,
+    pub out_of_tape_halt_text: &'static str,
+    pub accepted_halt_text: &'static str,
+    pub rejected_halt_text: &'static str,
+    pub loops_forever_halt_text: &'static str,
+}
+
+const HALT_REASONS: [HaltReason; 6] = [
+    HaltReason::NoRule,
+    HaltReason::Stop,
+    HaltReason::OutOfTape,
+    HaltReason::Accepted,
+    HaltReason::Rejected,
+    HaltReason::LoopsForever,
+];
+
+/// Choice of the expected halt reason, None if it isn't checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ExpectedHaltReason {
+    reason: Option<HaltReason>,
+    text: &'static str,
+}
+
+impl fmt::Display for ExpectedHaltReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+pub fn tests_scene<'a>(app: &'a App) -> Element<'a, Message> {
+    let TestsSceneTexts {
+        run_tests_button_text,
+        add_test_button_text,
+        add_test_description,
+        close_tests_button_text,
+        passed_tests_text,
+        ..
+    } = app.language.tests_scene_texts;
+
+    let passed_tests = app
+        .test_results
+        .iter()
+        .flatten()
+        .filter(|result| result.is_passed())
+        .count();
+    let summary = text(format!(
+        "{}: {}/{}",
+        passed_tests_text,
+        passed_tests,
+        app.tests.len()
+    ))
+    .size(30);
+
+    let run_tests_button = button(run_tests_button_text)
+        .padding(10)
+        .width(Length::Fill)
+        .on_press(Message::TestsRun);
+
+    let add_test_button = button(add_test_button_text)
+        .padding(10)
+        .width(Length::Fill)
+        .on_press(separate_edit(Message::TestAdded));
+
+    let close_tests_button = button(close_tests_button_text)
+        .padding(10)
+        .width(Length::Fill)
+        .on_press(Message::TestsSceneClosed);
+
+    let left_column = ui_column![
+        run_tests_button,
+        add_test_button,
+        text(add_test_description).size(16),
+        close_tests_button,
+    ]
+    .width(Length::Units(200))
+    .spacing(10);
+
+    let tests = app
+        .tests
+        .iter()
+        .zip(&app.test_results)
+        .enumerate()
+        .fold(ui_column![].spacing(20), |column, (i, (test, result))| {
+            column.push(test_view(app, i, test, result.as_ref()))
+        });
+
+    scene_frame(
+        summary.into(),
+        left_column.into(),
+        scrollable(tests).height(Length::Fill).into(),
+    )
+}
+
+/// Shows the name of the test, its input, the actual results and inputs of the expected ones.
+/// The actual results are empty while the test is running
+fn test_view<'a>(
+    app: &'a App,
+    index: usize,
+    test: &'a TestCase,
+    result: Option<&'a TestResult>,
+) -> Element<'a, Message> {
+    let texts = &app.language.tests_scene_texts;
+
+    let status = match result {
+        Some(result) if result.is_passed() => text(texts.passed_text).style(app.palette.success),
+        Some(_) => text(texts.failed_text).style(app.palette.danger),
+        None => text(texts.running_text),
+    };
+
+    let name_input = text_input(texts.test_name_input_placeholder, &test.name, move |name| {
+        Message::TestNameChanged(name, index)
+    })
+    .padding(10);
+
+    let remove_button = button(texts.remove_test_button_text)
+        .padding(10)
+        .style(theme::Button::Destructive)
+        .on_press(separate_edit(Message::TestRemoved(index)));
+
+    let input = text(format!(
+        "{}: {}   {}: {}",
+        texts.input_text, test.tape, texts.cursor_position_text, test.cursor_position
+    ));
+
+    let result_tape = result.map_or("", |result| result.tape.as_str());
+    let tape = text(format!("{}: {}", texts.tape_text, result_tape));
+    let expected_tape_input = text_input(
+        texts.not_checked_text,
+        test.expected_tape.as_deref().unwrap_or(""),
+        move |tape| Message::TestExpectedTapeChanged(tape, index),
+    )
+    .padding(10);
+
+    let halt_reason = match result.map(|result| result.halt_reason) {
+        Some(Some(reason)) => describe_halt_reason(app, reason),
+        Some(None) => texts.step_limit_reached_text,
+        None => "",
+    };
+    let halt_reason = text(format!("{}: {}", texts.halt_reason_text, halt_reason));
+    let expected_halt_reason = |reason: Option<HaltReason>| ExpectedHaltReason {
+        reason,
+        text: match reason {
+            Some(reason) => describe_halt_reason(app, reason),
+            None => texts.not_checked_text,
+        },
+    };
+    let expected_halt_reasons: Vec<ExpectedHaltReason> = [None]
+        .into_iter()
+        .chain(HALT_REASONS.map(Some))
+        .map(expected_halt_reason)
+        .collect();
+    let expected_halt_reason_pick_list = pick_list(
+        expected_halt_reasons,
+        Some(expected_halt_reason(test.expected_halt_reason)),
+        move |expected| {
+            separate_edit(Message::TestExpectedHaltReasonChanged(
+                expected.reason,
+                index,
+            ))
+        },
+    )
+    .padding(10);
+
+    let steps = result.map_or(String::new(), |result| result.steps.to_string());
+    let steps = text(format!("{}: {}", texts.steps_text, steps));
+    let max_steps_input = numeric_input(
+        texts.max_steps_text,
+        test.max_steps,
+        &app.focused_widget,
+        move |max_steps| Message::TestMaxStepsChanged(max_steps, index),
+    )
+    .can_be_negative(false)
+    .wrap_button_message(separate_edit);
+
+    let expected_row = |label: &'a str, input: Element<'a, Message>| {
+        row![text(label), input]
+            .spacing(10)
+            .align_items(Alignment::Center)
+    };
+    let details = ui_column![
+        input,
+        tape,
+        expected_row(texts.expected_text, expected_tape_input.into()),
+        halt_reason,
+        expected_row(texts.expected_text, expected_halt_reason_pick_list.into()),
+        steps,
+        expected_row(texts.max_steps_text, max_steps_input.into()),
+    ];
+
+    container(
+        ui_column![
+            row![status, name_input, remove_button]
+                .spacing(10)
+                .align_items(Alignment::Center),
+            details.spacing(5),
+        ]
+        .spacing(10),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .width(Length::Fill)
+    .into()
+}
+
+fn describe_halt_reason(app: &App, reason: HaltReason) -> &'static str {
+    let texts = &app.language.tests_scene_texts;
+    match reason {
+        HaltReason::NoRule => texts.no_rule_halt_text,
+        HaltReason::Stop => texts.stop_halt_text,
+        HaltReason::OutOfTape => texts.out_of_tape_halt_text,
+        HaltReason::Accepted => texts.accepted_halt_text,
+        HaltReason::Rejected => texts.rejected_halt_text,
+        HaltReason::LoopsForever => texts.loops_forever_halt_text,
+    }
+}
@@ -0,0 +1,147 @@
+use image::{Rgb, RgbImage};
+use turing_machine::{constants::EMPTY_CHAR, Machine, Table, Tape};
+
+/// Default width and height of a single cell in pixels
+pub const DEFAULT_CELL_SIZE: u32 = 4;
+
+/// Default number of steps rendered by the GUI and the `run` command
+pub const DEFAULT_SPACE_TIME_STEPS: usize = 1000;
+
+/// Blank cells added on both sides of the used part of the tape
+const MARGIN_CELLS: isize = 2;
+
+const BLANK_COLOR: Rgb<u8> = Rgb([255, 255, 255]);
+const HEAD_COLOR: Rgb<u8> = Rgb([214, 39, 40]);
+
+/// Colors of characters other than EMPTY_CHAR, repeated if there are more characters
+const CHAR_COLORS: [Rgb<u8>; 9] = [
+    Rgb([0, 0, 0]),
+    Rgb([31, 119, 180]),
+    Rgb([44, 160, 44]),
+    Rgb([255, 127, 14]),
+    Rgb([148, 103, 189]),
+    Rgb([140, 86, 75]),
+    Rgb([227, 119, 194]),
+    Rgb([127, 127, 127]),
+    Rgb([23, 190, 207]),
+];
+
+/// Tape after a single step, without blank cells on its ends
+struct Row {
+    /// Position of the first char, in the same coordinates as the cursor position
+    first_char_position: isize,
+    chars: Vec<char>,
+    cursor_position: isize,
+}
+
+/// Image in which every row is the tape after one step of the machine
+#[derive(Default)]
+pub struct SpaceTimeDiagram {
+    rows: Vec<Row>,
+}
+
+impl SpaceTimeDiagram {
+    pub fn new() -> Self {
+        Self { rows: Vec::new() }
+    }
+
+    /// Runs the machine from `tape` until it halts or executes `max_steps` steps
+    /// and records the tape after every step
+    pub fn new_from_run(table: &Table, tape: Tape, max_steps: usize) -> Self {
+        let mut diagram = Self::new();
+        let mut machine = Machine::without_history();
+        machine.reset(tape);
+        diagram.push(machine.get_tape());
+
+        while !machine.is_halted() && machine.get_step() < max_steps {
+            machine.next_step(table);
+            diagram.push(machine.get_tape());
+        }
+
+        diagram
+    }
+
+    /// Records the next row
+    pub fn push(&mut self, tape: &Tape) {
+        let chars = tape.get_chars_with_margin();
+        let first_index = chars.iter().position(|c| *c != EMPTY_CHAR);
+        let last_index = chars.iter().rposition(|c| *c != EMPTY_CHAR);
+
+        let (first_index, chars) = match (first_index, last_index) {
+            (Some(first), Some(last)) => (first, chars[first..=last].to_vec()),
+            _ => (0, Vec::new()),
+        };
+
+        self.rows.push(Row {
+            first_char_position: first_index as isize - tape.get_first_char_position(),
+            chars,
+            cursor_position: tape.get_cursor_position(),
+        });
+    }
+
+    /// Draws the rows with `cell_size` pixels per cell. Characters are colored
+    /// in the order of `characters` and the cursor is marked with a red square
+    pub fn render(&self, characters: &str, cell_size: u32) -> RgbImage {
+        let mut characters: Vec<char> = characters.chars().filter(|c| *c != EMPTY_CHAR).collect();
+        let (mut min_position, mut max_position) = (isize::MAX, isize::MIN);
+
+        for row in &self.rows {
+            min_position = min_position.min(row.cursor_position);
+            max_position = max_position.max(row.cursor_position);
+
+            if !row.chars.is_empty() {
+                min_position = min_position.min(row.first_char_position);
+                max_position =
+                    max_position.max(row.first_char_position + row.chars.len() as isize - 1);
+            }
+
+            // Characters missing in the table get the next colors
+            for c in &row.chars {
+                if *c != EMPTY_CHAR && !characters.contains(c) {
+                    characters.push(*c);
+                }
+            }
+        }
+
+        let first_position = min_position - MARGIN_CELLS;
+        let width = (max_position - first_position + 1 + MARGIN_CELLS) as u32;
+        let mut image = RgbImage::from_pixel(
+            width * cell_size,
+            self.rows.len() as u32 * cell_size,
+            BLANK_COLOR,
+        );
+
+        let mut fill_square = |column: u32, row: u32, offset: u32, size: u32, color| {
+            for x in 0..size {
+                for y in 0..size {
+                    let x = column * cell_size + offset + x;
+                    let y = row * cell_size + offset + y;
+                    image.put_pixel(x, y, color);
+                }
+            }
+        };
+
+        for (y, row) in self.rows.iter().enumerate() {
+            for (i, c) in row.chars.iter().enumerate() {
+                if let Some(index) = characters.iter().position(|ch| ch == c) {
+                    let column = (row.first_char_position + i as isize - first_position) as u32;
+                    let color = CHAR_COLORS[index % CHAR_COLORS.len()];
+                    fill_square(column, y as u32, 0, cell_size, color);
+                }
+            }
+
+            // The head covers half of the cell, so the character under it is still visible
+            let column = (row.cursor_position - first_position) as u32;
+            let head_size = (cell_size / 2).max(1);
+            fill_square(
+                column,
+                y as u32,
+                (cell_size - head_size) / 2,
+                head_size,
+                HEAD_COLOR,
+            );
+        }
+
+        image
+    }
+}
@@ -1,13 +1,32 @@
-pub mod create_tasks_table;
-mod blankable_input;
+mod parse_error;
 
 use crate::{
     constants::{DEFAULT_TABLE_CHARS, MAX_STATES_NUMBER, MIN_STATES_NUMBER},
     task::{Direction, Task},
 };
-use std::io::{prelude::*, Error, ErrorKind};
+pub use parse_error::{ExpectedToken, ParseError};
+use serde::{Deserialize, Serialize};
+use std::{
+    io::{prelude::*, Error, ErrorKind},
+    str::CharIndices,
+};
+
+const BYTE_ORDER_MARK: char = '\u{feff}';
+const ACCEPTING_STATE_MARKER: &str = "accept";
+const REJECTING_STATE_MARKER: &str = "reject";
+
+/// Kind of a state used for language recognition.
+/// Machine halts with a verdict when it enters an accepting or rejecting state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateKind {
+    #[default]
+    Normal,
+    Accepting,
+    Rejecting,
+}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Table {
     //// Number of possible states
     states_number: usize,
@@ -24,6 +43,18 @@ pub struct Table {
     /// the second one is index of certain character
     /// when characters string is sorted.
     tasks: Vec<Vec<Task>>,
+
+    /// Kinds of states, the index is number of state
+    state_kinds: Vec<StateKind>,
+
+    /// Optional names of states, empty if the state has no name
+    state_names: Vec<String>,
+
+    /// Optional comments describing states, empty if the state has no comment
+    state_comments: Vec<String>,
+
+    /// Positions of states in the state diagram, None if the state is placed automatically
+    state_positions: Vec<Option<(f32, f32)>>,
 }
 
 impl Table {
@@ -33,7 +64,7 @@ impl Table {
         let mut sorted_characters: Vec<_> = characters.chars().collect();
         sorted_characters.sort();
         let tasks: Vec<Vec<Task>> = (0..states_number)
-            .map(|_| (0..characters.len()).map(|_| Task::new()).collect())
+            .map(|_| (0..sorted_characters.len()).map(|_| Task::new()).collect())
             .collect();
 
         Self {
@@ -41,9 +72,15 @@ impl Table {
             characters,
             sorted_characters,
             tasks,
+            state_kinds: vec![StateKind::Normal; states_number],
+            state_names: vec![String::new(); states_number],
+            state_comments: vec![String::new(); states_number],
+            state_positions: vec![None; states_number],
         }
     }
 
+    /// Reads a table from a .mt file.
+    /// Invalid rows cause an InvalidData error wrapping a ParseError
     pub fn new_from_buffer(buffer: &mut impl BufRead) -> Result<Self, Error> {
         let mut table = Self::new_empty();
         let mut lines_iter = buffer.lines();
@@ -51,29 +88,31 @@ impl Table {
         let first_line = lines_iter
             .next()
             .ok_or(Error::from(ErrorKind::UnexpectedEof))??
+            .trim_start_matches(BYTE_ORDER_MARK)
             .filter_characters();
 
         table.set_characters(&first_line);
 
         for (task_state, line) in lines_iter.enumerate() {
             let line = line?;
-            let mut task_data_iterator = line.split_whitespace().array_chunks::<3>();
+            let mut tokens = Tokens::new(&line, task_state + 2);
 
             table.set_states_number(task_state + 1);
 
             for task_character in first_line.chars() {
-                let [state, character, direction] = task_data_iterator
-                    .next()
-                    .ok_or(Error::from(ErrorKind::InvalidData))?;
-
-                let state: usize = state.parse().or(Err(Error::from(ErrorKind::InvalidData)))?;
-                let character = character.chars().next().unwrap();
-                let direction: Direction = direction
-                    .chars()
-                    .next()
-                    .unwrap()
-                    .try_into()
-                    .or(Err(Error::from(ErrorKind::InvalidData)))?;
+                let (column, token) = tokens.next_expecting(ExpectedToken::StateNumber)?;
+                let state: usize = token
+                    .parse()
+                    .map_err(|_| tokens.error(column, token, ExpectedToken::StateNumber))?;
+
+                let (column, token) = tokens.next_expecting(ExpectedToken::Character)?;
+                let character = single_char(token)
+                    .ok_or_else(|| tokens.error(column, token, ExpectedToken::Character))?;
+
+                let (column, token) = tokens.next_expecting(ExpectedToken::Direction)?;
+                let direction: Direction = single_char(token)
+                    .and_then(|c| c.try_into().ok())
+                    .ok_or_else(|| tokens.error(column, token, ExpectedToken::Direction))?;
 
                 let task = Task {
                     state,
@@ -83,6 +122,22 @@ impl Table {
 
                 table.set_task_by_state_and_character(task, task_state, task_character);
             }
+
+            // States can be marked after all of their tasks
+            let state_kind = match tokens.next() {
+                None => StateKind::Normal,
+                Some((_, ACCEPTING_STATE_MARKER)) => StateKind::Accepting,
+                Some((_, REJECTING_STATE_MARKER)) => StateKind::Rejecting,
+                Some((column, token)) => {
+                    return Err(tokens.error(column, token, ExpectedToken::StateKindMarker))
+                }
+            };
+
+            if let Some((column, token)) = tokens.next() {
+                return Err(tokens.error(column, token, ExpectedToken::StateKindMarker));
+            }
+
+            table.set_state_kind(state_kind, task_state);
         }
 
         Ok(table)
@@ -96,16 +151,25 @@ impl Table {
 
         writeln!(buffer, "{}", &line[4..])?;
 
-        for row in &self.tasks {
+        for state in 0..self.states_number {
             line.clear();
 
-            for task in row {
+            // Tasks are stored in order of sorted characters,
+            // but they have to be written in the order of the first line
+            for character in self.characters.chars() {
+                let task = self.get_task(state, character).unwrap();
                 line += &format!(
                     "    {:0>2} {} {}",
                     task.state, task.character, task.direction
                 );
             }
 
+            match self.state_kinds[state] {
+                StateKind::Normal => {}
+                StateKind::Accepting => line += &format!("    {}", ACCEPTING_STATE_MARKER),
+                StateKind::Rejecting => line += &format!("    {}", REJECTING_STATE_MARKER),
+            }
+
             writeln!(buffer, "{}", &line[4..])?;
         }
 
@@ -144,30 +208,26 @@ impl Table {
         &self.characters
     }
 
-    pub fn set_characters(&mut self, new_characters: &String) {
+    pub fn get_sorted_characters(&self) -> &Vec<char> {
+        &self.sorted_characters
+    }
+
+    pub fn get_tasks(&self) -> &Vec<Vec<Task>> {
+        &self.tasks
+    }
+
+    pub fn set_characters(&mut self, new_characters: &str) {
         let filtered_new_characters = new_characters.filter_characters();
 
         let removed_characters: String = self
             .characters
             .chars()
-            .filter_map(|c| {
-                if filtered_new_characters.contains(c) {
-                    None
-                } else {
-                    Some(c)
-                }
-            })
+            .filter(|c| !filtered_new_characters.contains(*c))
             .collect();
 
         let added_characters: String = filtered_new_characters
             .chars()
-            .filter_map(|c| {
-                if self.characters.contains(c) {
-                    None
-                } else {
-                    Some(c)
-                }
-            })
+            .filter(|c| !self.characters.contains(*c))
             .collect();
 
         for removed_char in removed_characters.chars() {
@@ -201,25 +261,69 @@ impl Table {
         self.characters = filtered_new_characters;
     }
 
+    /// Returns the kind of `state` or StateKind::Normal if the state doesn't exist
+    pub fn get_state_kind(&self, state: usize) -> StateKind {
+        self.state_kinds.get(state).copied().unwrap_or_default()
+    }
+
+    pub fn set_state_kind(&mut self, kind: StateKind, state: usize) -> Option<()> {
+        *self.state_kinds.get_mut(state)? = kind;
+        Some(())
+    }
+
+    /// Returns the name of `state` or an empty string if the state has no name or doesn't exist
+    pub fn get_state_name(&self, state: usize) -> &str {
+        self.state_names.get(state).map_or("", String::as_str)
+    }
+
+    pub fn set_state_name(&mut self, name: String, state: usize) -> Option<()> {
+        *self.state_names.get_mut(state)? = name;
+        Some(())
+    }
+
+    /// Returns the comment of `state` or an empty string if the state has no comment or doesn't exist
+    pub fn get_state_comment(&self, state: usize) -> &str {
+        self.state_comments.get(state).map_or("", String::as_str)
+    }
+
+    pub fn set_state_comment(&mut self, comment: String, state: usize) -> Option<()> {
+        *self.state_comments.get_mut(state)? = comment;
+        Some(())
+    }
+
+    /// Returns the position of `state` in the state diagram
+    /// or None if it is placed automatically or doesn't exist
+    pub fn get_state_position(&self, state: usize) -> Option<(f32, f32)> {
+        self.state_positions.get(state).copied().flatten()
+    }
+
+    pub fn set_state_position(&mut self, position: Option<(f32, f32)>, state: usize) -> Option<()> {
+        *self.state_positions.get_mut(state)? = position;
+        Some(())
+    }
+
     pub fn get_states_number(&self) -> usize {
         self.states_number
     }
 
     pub fn set_states_number(&mut self, new_states_number: usize) {
-        let new_states_number = if new_states_number <= MIN_STATES_NUMBER {
-            MIN_STATES_NUMBER
-        } else if new_states_number >= MAX_STATES_NUMBER {
-            MAX_STATES_NUMBER
-        } else {
-            new_states_number
-        };
+        let new_states_number = new_states_number.clamp(MIN_STATES_NUMBER, MAX_STATES_NUMBER);
+
+        self.state_kinds
+            .resize(new_states_number, StateKind::Normal);
+        self.state_names.resize(new_states_number, String::new());
+        self.state_comments.resize(new_states_number, String::new());
+        self.state_positions.resize(new_states_number, None);
 
         if new_states_number < self.states_number {
             self.tasks.drain(new_states_number..);
         } else {
             for _ in self.states_number..new_states_number {
-                self.tasks
-                    .push((0..self.characters.len()).map(|_| Task::new()).collect())
+                self.tasks.push(
+                    (0..self.sorted_characters.len())
+                        .map(|_| Task::new())
+                        .collect(),
+                )
             }
         }
 
@@ -227,6 +331,79 @@ impl Table {
     }
 }
 
+/// Whitespace separated tokens of a single line of a .mt file
+/// together with columns at which they start
+struct Tokens<'a> {
+    line: &'a str,
+    line_number: usize,
+    char_indices: CharIndices<'a>,
+    column: usize,
+}
+
+impl<'a> Tokens<'a> {
+    fn new(line: &'a str, line_number: usize) -> Self {
+        Self {
+            line,
+            line_number,
+            char_indices: line.char_indices(),
+            column: 0,
+        }
+    }
+
+    /// Returns the next token or a ParseError pointing at the end of the line
+    fn next_expecting(&mut self, expected: ExpectedToken) -> Result<(usize, &'a str), Error> {
+        match self.next() {
+            Some(token) => Ok(token),
+            None => Err(self.error(self.column + 1, "", expected)),
+        }
+    }
+
+    fn error(&self, column: usize, token: &str, expected: ExpectedToken) -> Error {
+        Error::from(ParseError {
+            line: self.line_number,
+            column,
+            token: token.to_string(),
+            expected,
+        })
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = loop {
+            let (i, c) = self.char_indices.next()?;
+            self.column += 1;
+            if !c.is_whitespace() {
+                break i;
+            }
+        };
+
+        let start_column = self.column;
+        let mut end = self.line.len();
+
+        for (i, c) in self.char_indices.by_ref() {
+            self.column += 1;
+            if c.is_whitespace() {
+                end = i;
+                break;
+            }
+        }
+
+        Some((start_column, &self.line[start..end]))
+    }
+}
+
+/// Returns the only Unicode scalar of `s` or None if `s` doesn't consist of exactly one
+pub(crate) fn single_char(s: &str) -> Option<char> {
+    let mut chars = s.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
 trait FilterCharacters {
     /// Return string without duplicated characters and whitespaces
     fn filter_characters(&self) -> String;
@@ -242,3 +419,85 @@ impl FilterCharacters for str {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_error(rows: &str) -> ParseError {
+        let text = format!("#1\n{}\n", rows);
+        let error = Table::new_from_buffer(&mut text.as_bytes()).unwrap_err();
+        ParseError::from_io_error(&error).unwrap().clone()
+    }
+
+    fn expected_error(column: usize, token: &str, expected: ExpectedToken) -> ParseError {
+        ParseError {
+            line: 2,
+            column,
+            token: token.to_string(),
+            expected,
+        }
+    }
+
+    #[test]
+    fn valid_rows_are_read() {
+        let text = "#1\n01 1 + 00 # 0 accept\n";
+        let table = Table::new_from_buffer(&mut text.as_bytes()).unwrap();
+
+        assert_eq!(table.get_states_number(), 1);
+        assert_eq!(table.get_state_kind(0), StateKind::Accepting);
+        assert_eq!(
+            table.get_task(0, '#'),
+            Some(&Task {
+                state: 1,
+                character: '1',
+                direction: Direction::Right
+            })
+        );
+    }
+
+    #[test]
+    fn bad_state_number_is_reported() {
+        assert_eq!(
+            parse_error("x1 1 + 00 # 0"),
+            expected_error(1, "x1", ExpectedToken::StateNumber)
+        );
+    }
+
+    #[test]
+    fn multi_scalar_character_is_reported() {
+        // The accent is a separate scalar value combined with the letter
+        assert_eq!(
+            parse_error("01 e\u{301} + 00 # 0"),
+            expected_error(4, "e\u{301}", ExpectedToken::Character)
+        );
+    }
+
+    #[test]
+    fn bad_direction_is_reported() {
+        assert_eq!(
+            parse_error("01 1 > 00 # 0"),
+            expected_error(6, ">", ExpectedToken::Direction)
+        );
+    }
+
+    #[test]
+    fn wrong_state_kind_marker_is_reported() {
+        assert_eq!(
+            parse_error("01 1 + 00 # 0 accpt"),
+            expected_error(15, "accpt", ExpectedToken::StateKindMarker)
+        );
+        assert_eq!(
+            parse_error("01 1 + 00 # 0 accept reject"),
+            expected_error(22, "reject", ExpectedToken::StateKindMarker)
+        );
+    }
+
+    #[test]
+    fn short_row_is_reported_at_its_end() {
+        assert_eq!(
+            parse_error("01 1 + 00 #"),
+            expected_error(12, "", ExpectedToken::Direction)
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use core::fmt;
+use std::{
+    error,
+    io::{Error, ErrorKind},
+};
+
+/// Form of a token which the .mt parser expected to find
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpectedToken {
+    /// Number of the next state of a task
+    StateNumber,
+
+    /// Single character written by a task
+    Character,
+
+    /// Direction of a task: `+`, `-` or `0`
+    Direction,
+
+    /// `accept` or `reject` marker of a state or the end of the line
+    StateKindMarker,
+}
+
+impl fmt::Display for ExpectedToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ExpectedToken::StateNumber => "state number",
+            ExpectedToken::Character => "character",
+            ExpectedToken::Direction => "direction (+, - or 0)",
+            ExpectedToken::StateKindMarker => "accept, reject or end of line",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+/// Description of the place where reading a .mt file failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Number of the line, starting from 1
+    pub line: usize,
+
+    /// Number of the character in the line at which the token starts, starting from 1
+    pub column: usize,
+
+    /// The offending token, empty if the line ended too early
+    pub token: String,
+
+    pub expected: ExpectedToken,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found ",
+            self.line, self.column, self.expected
+        )?;
+
+        match self.token.is_empty() {
+            true => write!(f, "end of line"),
+            false => write!(f, "\"{}\"", self.token),
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+impl From<ParseError> for Error {
+    fn from(parse_error: ParseError) -> Self {
+        Error::new(ErrorKind::InvalidData, parse_error)
+    }
+}
+
+impl ParseError {
+    /// Returns the parse error wrapped in `error` if there is any
+    pub fn from_io_error(error: &Error) -> Option<&ParseError> {
+        error.get_ref()?.downcast_ref()
+    }
+}
@@ -1,16 +1,34 @@
-pub mod create_tape_preview;
-
 use crate::constants::{DEFAULT_TAPE_CHARS_NUMBER, EMPTY_CHAR, MAX_TAPE_LENGTH, MIN_TAPE_LENGTH};
-use std::iter::repeat;
+use serde::{Deserialize, Serialize};
+use std::iter::repeat_n;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TapeMode {
+    /// The tape has a fixed length and the machine halts
+    /// when the cursor would go beyond it
+    Bounded,
+
+    /// The tape grows on demand in both directions
+    /// and new cells are filled with EMPTY_CHARs
+    Unbounded,
+}
 
-#[derive(Clone)]
+/// Returned when a bounded tape's cursor would go beyond it
+#[derive(Debug, PartialEq, Eq)]
+pub struct OutOfTapeError;
+
+#[derive(Debug, Clone)]
 pub struct Tape {
     // Legnth of the tape
     length: usize,
 
-    /// String with len() == self.legnth, with characters in the middle
-    /// set using `set_chars` and filled with EMPTY_CHARs on left and right end
-    chars: String,
+    mode: TapeMode,
+
+    /// Vector with len() == self.legnth, with characters in the middle
+    /// set using `set_chars` and filled with EMPTY_CHARs on left and right end.
+    /// Each element is a single Unicode scalar, so the tape can be indexed by position
+    chars: Vec<char>,
 
     /// Position of cursor relative to `first_char_position`
     cursor_position: isize,
@@ -22,6 +40,12 @@ pub struct Tape {
     post_last_char_position: isize,
 }
 
+impl Default for Tape {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Tape {
     pub fn new() -> Self {
         let length = DEFAULT_TAPE_CHARS_NUMBER;
@@ -29,40 +53,38 @@ impl Tape {
 
         Self {
             length,
-            chars: repeat(EMPTY_CHAR).take(length).collect(),
+            mode: TapeMode::Bounded,
+            chars: repeat_n(EMPTY_CHAR, length).collect(),
             cursor_position: 0,
             first_char_position: position_zero,
             post_last_char_position: position_zero,
         }
     }
 
-    pub fn get_chars_with_margin(&self) -> &String {
+    pub fn get_chars_with_margin(&self) -> &[char] {
         &self.chars
     }
 
-    pub fn get_chars_without_margin(&self) -> &str {
+    pub fn get_chars_without_margin(&self) -> &[char] {
         let range = self.first_char_position as usize..self.post_last_char_position as usize;
         &self.chars[range]
     }
 
-    pub fn set_chars(&mut self, mut new_chars: String) {
-        if new_chars.len() > self.length {
-            new_chars.drain(self.length..);
+    /// Puts `new_chars` in the middle of the tape. A bounded tape keeps only as many
+    /// characters as its length allows, while an unbounded one grows to fit all of them
+    pub fn set_chars(&mut self, new_chars: String) {
+        let mut new_chars: Vec<char> = new_chars.chars().filter(|c| !c.is_whitespace()).collect();
+        match self.mode {
+            TapeMode::Bounded => new_chars.truncate(self.length),
+            TapeMode::Unbounded => self.length = self.length.max(new_chars.len()),
         }
 
-        new_chars = new_chars.chars().fold("".to_string(), |mut acc, c| {
-            if !c.is_whitespace() {
-                acc.push(c);
-            }
-            acc
-        });
-
         let first_char_position = (self.length - new_chars.len()) / 2;
         let post_last_char_position = first_char_position + new_chars.len();
         let replace_range = first_char_position..post_last_char_position;
 
-        self.chars = repeat(EMPTY_CHAR).take(self.length).collect();
-        self.chars.replace_range(replace_range, &new_chars);
+        self.chars = repeat_n(EMPTY_CHAR, self.length).collect();
+        self.chars.splice(replace_range, new_chars);
 
         self.first_char_position = first_char_position as isize;
         self.post_last_char_position = post_last_char_position as isize;
@@ -91,15 +113,21 @@ impl Tape {
     }
 
     pub fn set_length(&mut self, new_length: usize) {
-        self.length = if new_length <= MIN_TAPE_LENGTH {
-            MIN_TAPE_LENGTH
-        } else if new_length >= MAX_TAPE_LENGTH {
-            MAX_TAPE_LENGTH
-        } else {
-            new_length
-        };
+        self.length = new_length.clamp(MIN_TAPE_LENGTH, MAX_TAPE_LENGTH);
+
+        self.set_chars(self.get_chars_without_margin().iter().collect());
+    }
+
+    pub fn get_mode(&self) -> TapeMode {
+        self.mode
+    }
 
-        self.set_chars(self.get_chars_without_margin().to_string());
+    /// Switching to a bounded tape limits its length and characters again
+    pub fn set_mode(&mut self, new_mode: TapeMode) {
+        self.mode = new_mode;
+        if new_mode == TapeMode::Bounded {
+            self.set_length(self.length);
+        }
     }
 
     pub fn get_first_char_position(&self) -> isize {
@@ -107,33 +135,54 @@ impl Tape {
     }
 
     pub fn get_current_char(&self) -> char {
-        self.chars
-            .chars()
-            .nth((self.first_char_position + self.cursor_position) as usize)
-            .unwrap()
+        self.chars[(self.first_char_position + self.cursor_position) as usize]
     }
 
     pub fn set_current_char(&mut self, new_char: char) {
         let position = (self.first_char_position + self.cursor_position) as usize;
-        self.chars
-            .replace_range(position..=position, &format!("{}", new_char))
+        self.chars[position] = new_char;
     }
 
-    pub fn move_cursor_left(&mut self) -> Result<(), ()> {
-        if self.first_char_position + self.cursor_position - 1 >= 0 {
-            self.cursor_position -= 1;
-            Ok(())
-        } else {
-            Err(())
+    pub fn move_cursor_left(&mut self) -> Result<(), OutOfTapeError> {
+        if self.first_char_position + self.cursor_position - 1 < 0 {
+            if self.mode == TapeMode::Bounded {
+                return Err(OutOfTapeError);
+            }
+            self.extend_left();
         }
+
+        self.cursor_position -= 1;
+        Ok(())
     }
 
-    pub fn move_cursor_right(&mut self) -> Result<(), ()> {
-        if self.first_char_position + self.cursor_position + 1 < self.length as isize {
-            self.cursor_position += 1;
-            Ok(())
-        } else {
-            Err(())
+    pub fn move_cursor_right(&mut self) -> Result<(), OutOfTapeError> {
+        if self.first_char_position + self.cursor_position + 1 >= self.length as isize {
+            if self.mode == TapeMode::Bounded {
+                return Err(OutOfTapeError);
+            }
+            self.extend_right();
         }
+
+        self.cursor_position += 1;
+        Ok(())
+    }
+
+    /// Prepends EMPTY_CHARs to the tape, doubling its length
+    fn extend_left(&mut self) {
+        let extension_length = self.length;
+
+        self.chars
+            .splice(0..0, repeat_n(EMPTY_CHAR, extension_length));
+        self.length += extension_length;
+        self.first_char_position += extension_length as isize;
+        self.post_last_char_position += extension_length as isize;
+    }
+
+    /// Appends EMPTY_CHARs to the tape, doubling its length
+    fn extend_right(&mut self) {
+        let extension_length = self.length;
+
+        self.chars.extend(repeat_n(EMPTY_CHAR, extension_length));
+        self.length += extension_length;
     }
 }
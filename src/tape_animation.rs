@@ -0,0 +1,279 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use ab_glyph::{Font, FontRef, PxScale, PxScaleFont, ScaleFont};
+use image::{
+    codecs::gif::{GifEncoder, Repeat},
+    Delay, Frame, ImageResult, Rgba, RgbaImage,
+};
+use turing_machine::{constants::EMPTY_CHAR, Machine, Table, Tape};
+
+use crate::gui_constants::TAPE_FONT_BYTES;
+
+/// Default number of steps recorded by the GUI
+pub const DEFAULT_ANIMATION_STEPS: usize = 100;
+
+/// Default time for which every frame is shown in milliseconds
+pub const DEFAULT_FRAME_DELAY: u32 = 200;
+
+const FONT_SIZE: f32 = 28.0;
+const CELL_WIDTH: u32 = 32;
+const LINE_HEIGHT: u32 = 40;
+const MARGIN: u32 = 16;
+const MARKER_HEIGHT: u32 = 10;
+const FRAME_HEIGHT: u32 = 2 * MARGIN + 2 * LINE_HEIGHT + MARKER_HEIGHT;
+
+/// Maximum number of cells in a frame. Longer tapes are shown around the cursor
+const MAX_VISIBLE_CELLS: usize = 41;
+
+/// Cells added on both sides of the part of the tape used during the run
+const MARGIN_CELLS: isize = 2;
+
+/// Number of shades of antialiased text. Frames with at most 256 colors
+/// are saved without the slow color quantization of GIF encoding
+const COVERAGE_LEVELS: f32 = 8.0;
+
+const BACKGROUND_COLOR: Rgba<u8> = Rgba([255, 255, 255, 255]);
+const TEXT_COLOR: Rgba<u8> = Rgba([0, 0, 0, 255]);
+const HEAD_COLOR: Rgba<u8> = Rgba([214, 39, 40, 255]);
+const HEAD_CELL_COLOR: Rgba<u8> = Rgba([255, 224, 224, 255]);
+
+/// Configuration of the machine after a single step
+struct Snapshot {
+    tape: Tape,
+    state: usize,
+    step: usize,
+}
+
+/// Animation in which every frame shows the tape, the head,
+/// the state and the step of the machine after one step
+#[derive(Default)]
+pub struct TapeAnimation {
+    snapshots: Vec<Snapshot>,
+}
+
+impl TapeAnimation {
+    pub fn new() -> Self {
+        Self {
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Runs the machine from `tape` until it halts or executes `max_steps` steps
+    /// and records it after every step
+    pub fn new_from_run(table: &Table, tape: Tape, max_steps: usize) -> Self {
+        let mut animation = Self::new();
+        let mut machine = Machine::without_history();
+        machine.reset(tape);
+        animation.push(&machine);
+
+        while !machine.is_halted() && machine.get_step() < max_steps {
+            machine.next_step(table);
+            animation.push(&machine);
+        }
+
+        animation
+    }
+
+    /// Records the next frame
+    pub fn push(&mut self, machine: &Machine) {
+        self.snapshots.push(Snapshot {
+            tape: machine.get_tape().clone(),
+            state: machine.get_state(),
+            step: machine.get_step(),
+        });
+    }
+
+    /// Saves the frames as a looping GIF. Every frame is shown for `frame_delay` milliseconds
+    /// and states are labelled with their names from `table` if they have any
+    pub fn save(
+        &self,
+        path: &Path,
+        table: &Table,
+        state_text: &str,
+        step_text: &str,
+        frame_delay: u32,
+    ) -> ImageResult<()> {
+        let font = FontRef::try_from_slice(TAPE_FONT_BYTES).expect("the tape font is valid");
+        let font = font.into_scaled(PxScale::from(FONT_SIZE));
+        let (first_position, visible_cells) = self.get_visible_cells();
+
+        let statuses: Vec<String> = self
+            .snapshots
+            .iter()
+            .map(|snapshot| {
+                let state = match table.get_state_name(snapshot.state) {
+                    "" => snapshot.state.to_string(),
+                    name => name.to_string(),
+                };
+                format!(
+                    "{}: {}   {}: {}",
+                    state_text, state, step_text, snapshot.step
+                )
+            })
+            .collect();
+
+        // All frames have the same size, so they are wide enough for the longest status
+        let width = statuses
+            .iter()
+            .map(|status| get_text_width(&font, status))
+            .fold(visible_cells as u32 * CELL_WIDTH, u32::max)
+            + 2 * MARGIN;
+
+        let mut encoder = GifEncoder::new(BufWriter::new(File::create(path)?));
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        for (snapshot, status) in self.snapshots.iter().zip(&statuses) {
+            let first_position = match first_position {
+                Some(position) => position,
+                None => snapshot.tape.get_cursor_position() - (visible_cells / 2) as isize,
+            };
+
+            let mut image = RgbaImage::from_pixel(width, FRAME_HEIGHT, BACKGROUND_COLOR);
+            draw_text(&mut image, &font, status, MARGIN, MARGIN, TEXT_COLOR);
+            draw_tape(
+                &mut image,
+                &font,
+                &snapshot.tape,
+                first_position,
+                visible_cells,
+            );
+
+            let delay = Delay::from_numer_denom_ms(frame_delay, 1);
+            encoder.encode_frame(Frame::from_parts(image, 0, 0, delay))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the first visible position and the number of visible cells.
+    /// All non-blank cells and cursor positions of the run are shown if they fit
+    /// in MAX_VISIBLE_CELLS cells, otherwise the view follows the cursor and the position is None
+    fn get_visible_cells(&self) -> (Option<isize>, usize) {
+        let (mut min_position, mut max_position) = (isize::MAX, isize::MIN);
+
+        for snapshot in &self.snapshots {
+            let tape = &snapshot.tape;
+            min_position = min_position.min(tape.get_cursor_position());
+            max_position = max_position.max(tape.get_cursor_position());
+
+            let chars = tape.get_chars_with_margin();
+            let first_index = chars.iter().position(|c| *c != EMPTY_CHAR);
+            let last_index = chars.iter().rposition(|c| *c != EMPTY_CHAR);
+            if let (Some(first), Some(last)) = (first_index, last_index) {
+                min_position = min_position.min(first as isize - tape.get_first_char_position());
+                max_position = max_position.max(last as isize - tape.get_first_char_position());
+            }
+        }
+
+        let first_position = min_position - MARGIN_CELLS;
+        let visible_cells = (max_position - first_position + 1 + MARGIN_CELLS) as usize;
+
+        match visible_cells <= MAX_VISIBLE_CELLS {
+            true => (Some(first_position), visible_cells),
+            false => (None, MAX_VISIBLE_CELLS),
+        }
+    }
+}
+
+/// Draws `visible_cells` cells of the tape starting with `first_position`
+/// under the status line and the head marker under the current cell
+fn draw_tape(
+    image: &mut RgbaImage,
+    font: &PxScaleFont<FontRef<'static>>,
+    tape: &Tape,
+    first_position: isize,
+    visible_cells: usize,
+) {
+    let chars = tape.get_chars_with_margin();
+    let y = MARGIN + LINE_HEIGHT;
+
+    for cell in 0..visible_cells {
+        let position = first_position + cell as isize;
+        let index = tape.get_first_char_position() + position;
+        let c = match usize::try_from(index)
+            .ok()
+            .and_then(|index| chars.get(index))
+        {
+            Some(c) => *c,
+            None => EMPTY_CHAR,
+        };
+
+        let x = MARGIN + cell as u32 * CELL_WIDTH;
+        let color = match position == tape.get_cursor_position() {
+            true => {
+                fill_rectangle(image, x, y, CELL_WIDTH, LINE_HEIGHT, HEAD_CELL_COLOR);
+                draw_head_marker(image, x, y + LINE_HEIGHT);
+                HEAD_COLOR
+            }
+            false => TEXT_COLOR,
+        };
+
+        let text = c.to_string();
+        let text_x = x + CELL_WIDTH.saturating_sub(get_text_width(font, &text)) / 2;
+        draw_text(image, font, &text, text_x, y, color);
+    }
+}
+
+fn get_text_width(font: &PxScaleFont<FontRef<'static>>, text: &str) -> u32 {
+    let width: f32 = text.chars().map(|c| font.h_advance(font.glyph_id(c))).sum();
+    width.ceil() as u32
+}
+
+/// Draws `text` in a line with its top left corner at (`x`, `y`)
+fn draw_text(
+    image: &mut RgbaImage,
+    font: &PxScaleFont<FontRef<'static>>,
+    text: &str,
+    x: u32,
+    y: u32,
+    color: Rgba<u8>,
+) {
+    let baseline = y as f32 + (LINE_HEIGHT as f32 + font.ascent() + font.descent()) / 2.0;
+    let mut left = x as f32;
+
+    for c in text.chars() {
+        let mut glyph = font.scaled_glyph(c);
+        glyph.position = ab_glyph::point(left, baseline);
+        left += font.h_advance(glyph.id);
+
+        let outline = match font.outline_glyph(glyph) {
+            Some(outline) => outline,
+            None => continue,
+        };
+        let bounds = outline.px_bounds();
+
+        outline.draw(|glyph_x, glyph_y, coverage| {
+            let x = bounds.min.x as i32 + glyph_x as i32;
+            let y = bounds.min.y as i32 + glyph_y as i32;
+            if x < 0 || y < 0 || x >= image.width() as i32 || y >= image.height() as i32 {
+                return;
+            }
+
+            let coverage = (coverage * COVERAGE_LEVELS).round() / COVERAGE_LEVELS;
+            let pixel = image.get_pixel_mut(x as u32, y as u32);
+            for channel in 0..3 {
+                let blended =
+                    pixel[channel] as f32 * (1.0 - coverage) + color[channel] as f32 * coverage;
+                pixel[channel] = blended.round() as u8;
+            }
+        });
+    }
+}
+
+/// Draws a triangle pointing up at the cell starting at `x`
+fn draw_head_marker(image: &mut RgbaImage, x: u32, y: u32) {
+    let middle = x + CELL_WIDTH / 2;
+    let top = y + MARGIN / 4;
+
+    for row in 0..MARKER_HEIGHT {
+        fill_rectangle(image, middle - row, top + row, 2 * row + 1, 1, HEAD_COLOR);
+    }
+}
+
+fn fill_rectangle(image: &mut RgbaImage, x: u32, y: u32, width: u32, height: u32, color: Rgba<u8>) {
+    for y in y..(y + height).min(image.height()) {
+        for x in x..(x + width).min(image.width()) {
+            image.put_pixel(x, y, color);
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use core::fmt;
 
+use serde::{Deserialize, Serialize};
+
 use crate::constants::{DEFAULT_STATE, DEFAULT_TASK_CHAR, DEFAULT_TASK_DIRECTION};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Direction {
     Left,
     Right,
@@ -40,13 +43,19 @@ impl fmt::Display for Direction {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Task {
     pub state: usize,
     pub character: char,
     pub direction: Direction,
 }
 
+impl Default for Task {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Task {
     pub fn new() -> Self {
         Self {
@@ -55,4 +64,26 @@ impl Task {
             direction: DEFAULT_TASK_DIRECTION,
         }
     }
+
+    /// Creates a task which halts the machine without changing the tape or the state
+    pub fn new_halting(state: usize, character: char) -> Self {
+        Self {
+            state,
+            character,
+            direction: Direction::Stop,
+        }
+    }
+
+    /// Returns true if the task is the same as a newly created one,
+    /// which means it hasn't been filled in
+    pub fn is_default(&self) -> bool {
+        *self == Self::new()
+    }
+
+    /// Returns true if the task in the cell of `state` and `read` is default or halts
+    /// the machine without changing anything, so exported tables need no transition for it.
+    /// Only default cells have no rule when the machine runs, other ones stop it
+    pub fn is_halting_for(&self, state: usize, read: char) -> bool {
+        self.is_default() || *self == Self::new_halting(state, read)
+    }
 }
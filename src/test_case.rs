@@ -0,0 +1,274 @@
+use core::fmt;
+use std::{
+    fs::File,
+    io::{prelude::*, BufReader, Error},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{DEFAULT_TEST_MAX_STEPS, EMPTY_CHAR},
+    machine::{HaltReason, Machine},
+    table::Table,
+    tape::{Tape, TapeMode},
+};
+
+/// Named run of a table with the expected result.
+/// The mode and the length of the tape are taken from the project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestCase {
+    pub name: String,
+
+    /// Initial characters of the tape
+    pub tape: String,
+
+    #[serde(default)]
+    pub cursor_position: isize,
+
+    /// Final tape without blank characters on its ends, not checked if None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_tape: Option<String>,
+
+    /// Not checked if None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_halt_reason: Option<HaltReason>,
+
+    /// The test fails if the machine doesn't halt within this number of steps
+    #[serde(default = "default_max_steps")]
+    pub max_steps: usize,
+}
+
+/// Reason why a test case has failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestFailure {
+    /// The input doesn't fit on the bounded tape of the project, so it was cut
+    InputTooLong {
+        length: usize,
+    },
+
+    /// The machine didn't halt within the step budget
+    StepLimitReached,
+
+    WrongTape {
+        expected: String,
+        actual: String,
+    },
+
+    WrongHaltReason {
+        expected: HaltReason,
+        actual: HaltReason,
+    },
+}
+
+impl fmt::Display for TestFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TestFailure::InputTooLong { length } => {
+                write!(f, "input longer than the tape of length {}", length)
+            }
+            TestFailure::StepLimitReached => write!(f, "step limit reached"),
+            TestFailure::WrongTape { expected, actual } => {
+                write!(f, "expected tape {}, got {}", expected, actual)
+            }
+            TestFailure::WrongHaltReason { expected, actual } => {
+                write!(
+                    f,
+                    "expected halt reason \"{}\", got \"{}\"",
+                    expected, actual
+                )
+            }
+        }
+    }
+}
+
+/// Outcome of running a test case
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestResult {
+    pub steps: usize,
+
+    /// Final tape without blank characters on its ends
+    pub tape: String,
+
+    /// None if the step limit was reached
+    pub halt_reason: Option<HaltReason>,
+
+    /// Empty if the test has passed
+    pub failures: Vec<TestFailure>,
+}
+
+impl TestResult {
+    pub fn is_passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Test cases saved apart from the table, e.g. for .mt files
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TestSuite {
+    pub tests: Vec<TestCase>,
+}
+
+impl TestSuite {
+    pub fn new_from_buffer(buffer: &mut impl Read) -> Result<Self, Error> {
+        Ok(serde_json::from_reader(buffer)?)
+    }
+
+    pub fn new_from_file(path: &Path) -> Result<Self, Error> {
+        let file = File::open(path)?;
+        Self::new_from_buffer(&mut BufReader::new(file))
+    }
+
+    pub fn write_to_buffer(&self, buffer: &mut impl Write) -> Result<(), Error> {
+        serde_json::to_writer_pretty(&mut *buffer, self)?;
+        writeln!(buffer)
+    }
+}
+
+impl TestCase {
+    /// Creates a test case expecting the current result of running the table from `tape`.
+    /// Returns None if the machine doesn't halt within `max_steps`,
+    /// because the test would fail with any expected result
+    pub fn new_from_run(
+        name: String,
+        table: &Table,
+        tape: &Tape,
+        max_steps: usize,
+    ) -> Option<Self> {
+        let mut test_case = Self {
+            name,
+            tape: tape.get_chars_without_margin().iter().collect(),
+            cursor_position: tape.get_cursor_position(),
+            expected_tape: None,
+            expected_halt_reason: None,
+            max_steps,
+        };
+
+        let result = test_case.run(table, tape);
+        test_case.expected_tape = Some(result.tape);
+        test_case.expected_halt_reason = Some(result.halt_reason?);
+        Some(test_case)
+    }
+
+    /// Runs the table with the input of the test case on a tape
+    /// with the same mode and length as `project_tape`.
+    /// An input longer than a bounded tape is cut and the test fails
+    pub fn run(&self, table: &Table, project_tape: &Tape) -> TestResult {
+        let mut failures = Vec::new();
+        let input_length = self.tape.chars().filter(|c| !c.is_whitespace()).count();
+        if project_tape.get_mode() == TapeMode::Bounded && input_length > project_tape.get_length()
+        {
+            failures.push(TestFailure::InputTooLong {
+                length: project_tape.get_length(),
+            });
+        }
+
+        let mut tape = project_tape.clone();
+        tape.set_chars(self.tape.clone());
+        tape.set_cursor_position(self.cursor_position);
+
+        // Only tests expecting an infinite loop look for it, the others just reach their limit
+        let mut machine = Machine::without_history();
+        machine.set_cycle_detection(self.expected_halt_reason == Some(HaltReason::LoopsForever));
+        machine.reset(tape);
+        while !machine.is_halted() && machine.get_step() < self.max_steps {
+            machine.next_step(table);
+        }
+
+        let tape_chars: String = machine.get_tape().get_chars_with_margin().iter().collect();
+        let tape_chars = tape_chars.trim_matches(EMPTY_CHAR).to_string();
+        let halt_reason = machine.get_halt_reason();
+
+        match (halt_reason, self.expected_halt_reason) {
+            (None, _) => failures.push(TestFailure::StepLimitReached),
+            (Some(actual), Some(expected)) if actual != expected => {
+                failures.push(TestFailure::WrongHaltReason { expected, actual })
+            }
+            _ => {}
+        }
+
+        if let Some(expected) = &self.expected_tape {
+            let expected = expected.trim_matches(EMPTY_CHAR);
+            if expected != tape_chars {
+                failures.push(TestFailure::WrongTape {
+                    expected: expected.to_string(),
+                    actual: tape_chars.clone(),
+                });
+            }
+        }
+
+        TestResult {
+            steps: machine.get_step(),
+            tape: tape_chars,
+            halt_reason,
+            failures,
+        }
+    }
+}
+
+fn default_max_steps() -> usize {
+    DEFAULT_TEST_MAX_STEPS
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::task::{Direction, Task};
+
+    use super::*;
+
+    /// Table which moves right over 1s and halts on the first blank cell
+    fn new_table(direction_on_blank: Direction) -> Table {
+        let mut table = Table::new_empty();
+        table.set_characters("#1");
+        let task = |direction| Task {
+            state: 0,
+            character: '1',
+            direction,
+        };
+        table.set_task_by_state_and_character(task(Direction::Right), 0, '1');
+        table.set_task_by_state_and_character(task(direction_on_blank), 0, EMPTY_CHAR);
+        table
+    }
+
+    #[test]
+    fn new_test_expects_current_result() {
+        let table = new_table(Direction::Stop);
+        let mut tape = Tape::new();
+        tape.set_chars("11".to_string());
+        let test = TestCase::new_from_run("test".to_string(), &table, &tape, 100).unwrap();
+
+        assert_eq!(test.expected_tape.as_deref(), Some("111"));
+        assert_eq!(test.expected_halt_reason, Some(HaltReason::Stop));
+        assert!(test.run(&table, &tape).is_passed());
+    }
+
+    #[test]
+    fn new_test_is_refused_if_machine_does_not_halt() {
+        let table = new_table(Direction::Right);
+        let mut tape = Tape::new();
+        tape.set_mode(TapeMode::Unbounded);
+
+        assert_eq!(
+            TestCase::new_from_run("test".to_string(), &table, &tape, 100),
+            None
+        );
+    }
+
+    #[test]
+    fn input_longer_than_bounded_tape_fails() {
+        let table = new_table(Direction::Stop);
+        let mut tape = Tape::new();
+        tape.set_length(2);
+        let test = TestCase {
+            name: "test".to_string(),
+            tape: "111".to_string(),
+            cursor_position: 0,
+            expected_tape: None,
+            expected_halt_reason: None,
+            max_steps: 100,
+        };
+
+        let result = test.run(&table, &tape);
+        assert_eq!(result.failures, [TestFailure::InputTooLong { length: 2 }]);
+    }
+}